@@ -1,13 +1,14 @@
 #![feature(sync_unsafe_cell)]
 use std::cell::SyncUnsafeCell;
+use std::fmt;
 use std::fs::{File, OpenOptions};
 use std::io::Write;
 use std::ops::Deref;
 use std::path::PathBuf;
 
 use ron::extensions::Extensions;
-use serde::de::DeserializeOwned;
-use serde::Serialize;
+use serde::de::{DeserializeOwned, IgnoredAny, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
 
 /// Shared trait for every configuration of fht-shell.
 ///
@@ -23,6 +24,19 @@ pub trait Config: Clone + Default + Serialize + DeserializeOwned {
     /// These will be used to popular a default config file if the file was not found before.
     const DEFAULT_CONTENTS: &'static str = "";
 
+    /// The environment variable that disables creating the config file when it's missing.
+    ///
+    /// When set, a missing config file makes [`Config::load`] use [`Config::DEFAULT_CONTENTS`]
+    /// in-memory, without writing anything to disk. Useful for read-only setups, like NixOS.
+    const NO_CREATE_ENV_VAR: &'static str = "FHTC_NO_CREATE_CONFIG";
+
+    /// The current version of the config format.
+    ///
+    /// Config files can specify the version they are written for using a top-level `version`
+    /// field, files without it are at version 0. When loading a file with an older version, it
+    /// gets passed through [`Config::migrate`] before getting deserialized.
+    const VERSION: u32 = 0;
+
     fn get_path() -> PathBuf {
         xdg::BaseDirectories::new()
             .unwrap()
@@ -38,6 +52,12 @@ pub trait Config: Clone + Default + Serialize + DeserializeOwned {
         let reader = OpenOptions::new().read(true).write(false).open(config_path);
         let reader = match reader {
             Ok(reader) => reader,
+            Err(err)
+                if err.kind() == std::io::ErrorKind::NotFound
+                    && std::env::var_os(Self::NO_CREATE_ENV_VAR).is_some() =>
+            {
+                return Self::parse(Self::DEFAULT_CONTENTS.as_bytes());
+            }
             Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
                 // Create config file for user
                 let mut file = File::create_new(config_path).unwrap();
@@ -53,14 +73,164 @@ pub trait Config: Clone + Default + Serialize + DeserializeOwned {
             }
         };
 
-        ron::Options::default()
+        Self::parse(reader)
+    }
+
+    /// Parse the configuration from this reader, then process it with [`Config::post_load`]
+    ///
+    /// Documents written for an older [`Config::VERSION`] are migrated first.
+    fn parse(mut reader: impl std::io::Read) -> Result<Self, Error> {
+        let options = ron::Options::default()
             .with_default_extension(Extensions::IMPLICIT_SOME)
-            .with_default_extension(Extensions::UNWRAP_VARIANT_NEWTYPES)
-            .from_reader(reader)
-            .map_err(|err| Error::Parse(err.code))
+            .with_default_extension(Extensions::UNWRAP_VARIANT_NEWTYPES);
+
+        let mut contents = String::new();
+        reader.read_to_string(&mut contents)?;
+
+        // With version 0 there's nothing to migrate from.
+        if Self::VERSION != 0 {
+            let VersionedDocument { version } = options
+                .from_str(&contents)
+                .map_err(|err| Error::Parse(err.code))?;
+            if version < Self::VERSION {
+                contents = Self::migrate(version, contents)?;
+            }
+        }
+
+        let mut config: Self = options
+            .from_str(&contents)
+            .map_err(|err| Error::Parse(err.code))?;
+        config.post_load()?;
+
+        Ok(config)
+    }
+
+    /// Transform a config document written for `old_version` to the current [`Config::VERSION`].
+    ///
+    /// This is called with the whole document text, so that you can rename or move fields around
+    /// when the format changes, for example:
+    ///
+    /// ```rust,ignore
+    /// fn migrate(old_version: u32, mut contents: String) -> Result<String, Error> {
+    ///     if old_version < 1 {
+    ///         // Version 1 renamed `gaps` to `inner_gaps`.
+    ///         contents = rename_field(&contents, "gaps", "inner_gaps");
+    ///     }
+    ///     Ok(contents)
+    /// }
+    /// ```
+    ///
+    /// The document is kept as text since a [`ron::Value`] loses struct and enum names, and thus
+    /// can't always be deserialized back. The default implementation keeps the document as-is.
+    fn migrate(_old_version: u32, contents: String) -> Result<String, Error> {
+        Ok(contents)
+    }
+
+    /// Process the configuration after it got parsed from the file.
+    ///
+    /// You can use this to validate and expand values, like with [`interpolate_env`]
+    fn post_load(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Only the version of a config document, see [`Config::VERSION`]
+///
+/// Every other field of the document gets ignored, and the document can have any struct name.
+struct VersionedDocument {
+    version: u32,
+}
+
+impl<'de> Deserialize<'de> for VersionedDocument {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(field_identifier, rename_all = "lowercase")]
+        enum Field {
+            Version,
+            #[serde(other)]
+            Other,
+        }
+
+        struct VersionVisitor;
+
+        impl<'de> Visitor<'de> for VersionVisitor {
+            type Value = VersionedDocument;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a config struct")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut version = 0;
+                while let Some(field) = map.next_key()? {
+                    match field {
+                        Field::Version => version = map.next_value()?,
+                        Field::Other => _ = map.next_value::<IgnoredAny>()?,
+                    }
+                }
+
+                Ok(VersionedDocument { version })
+            }
+        }
+
+        // ron checks the struct name when deserializing a struct, but not with deserialize_any.
+        deserializer.deserialize_any(VersionVisitor)
     }
 }
 
+/// Rename the `old` field of a config document to `new`, to use inside [`Config::migrate`]
+///
+/// Only whole identifiers followed by a `:` get renamed, so renaming `gaps` keeps `outer_gaps`.
+pub fn rename_field(contents: &str, old: &str, new: &str) -> String {
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut ret = String::with_capacity(contents.len());
+    let mut last = 0;
+
+    for (start, _) in contents.match_indices(old) {
+        let before = &contents[..start];
+        let after = &contents[start + old.len()..];
+        if before.ends_with(is_ident)
+            || after.starts_with(is_ident)
+            || !after.trim_start().starts_with(':')
+        {
+            continue;
+        }
+
+        ret.push_str(&contents[last..start]);
+        ret.push_str(new);
+        last = start + old.len();
+    }
+    ret.push_str(&contents[last..]);
+
+    ret
+}
+
+/// Expand `${VAR}` references inside this string using the process environment.
+///
+/// Referencing an unset variable is an error, naming the variable. An unclosed `${` is kept as-is.
+pub fn interpolate_env(string: &str) -> Result<String, Error> {
+    let mut ret = String::with_capacity(string.len());
+    let mut rest = string;
+
+    while let Some(start) = rest.find("${") {
+        ret.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find('}') else {
+            // Unclosed reference, keep it.
+            rest = &rest[start..];
+            break;
+        };
+
+        let name = &after[..end];
+        let value = std::env::var(name).map_err(|_| Error::UndefinedEnvVar(name.to_string()))?;
+        ret.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    ret.push_str(rest);
+
+    Ok(ret)
+}
+
 /// A config wrapper to use any config struct statically.
 ///
 /// This type will be able to load
@@ -126,9 +296,91 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("Error while parsing the config: {0:?}")]
     Parse(#[from] ron::Error),
+    #[error("Undefined environment variable referenced in the config: {0}")]
+    UndefinedEnvVar(String),
 }
 
-// TODO: Write tests.
-//
-// This should be easy since we are using generics to parse everything, but still, have to write
-// down and think about which cases we should test here.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+    struct ExampleConfig {
+        #[serde(default)]
+        version: u32,
+        inner_gaps: i32,
+        outer_gaps: i32,
+        layout: ExampleLayout,
+    }
+
+    #[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+    enum ExampleLayout {
+        #[default]
+        Tile,
+        Floating,
+    }
+
+    impl Config for ExampleConfig {
+        const NAME: &'static str = "example";
+        const VERSION: u32 = 1;
+
+        fn migrate(old_version: u32, mut contents: String) -> Result<String, Error> {
+            if old_version < 1 {
+                // Version 1 renamed `gaps` to `inner_gaps`.
+                contents = rename_field(&contents, "gaps", "inner_gaps");
+            }
+            Ok(contents)
+        }
+    }
+
+    #[test]
+    fn migrates_older_documents() {
+        let config =
+            ExampleConfig::parse("(gaps: 8, outer_gaps: 2, layout: Floating)".as_bytes()).unwrap();
+        assert_eq!(
+            config,
+            ExampleConfig {
+                version: 0,
+                inner_gaps: 8,
+                outer_gaps: 2,
+                layout: ExampleLayout::Floating,
+            }
+        );
+    }
+
+    #[test]
+    fn migrates_named_documents() {
+        let contents = "ExampleConfig(\n    gaps: 8,\n    outer_gaps: 2,\n    layout: Floating,\n)";
+        let config = ExampleConfig::parse(contents.as_bytes()).unwrap();
+        assert_eq!(config.inner_gaps, 8);
+        assert_eq!(config.outer_gaps, 2);
+        assert_eq!(config.layout, ExampleLayout::Floating);
+    }
+
+    #[test]
+    fn keeps_current_documents() {
+        let contents = "ExampleConfig(version: 1, inner_gaps: 4, outer_gaps: 2, layout: Tile)";
+        let config = ExampleConfig::parse(contents.as_bytes()).unwrap();
+        assert_eq!(config.inner_gaps, 4);
+        assert_eq!(config.outer_gaps, 2);
+        assert_eq!(config.layout, ExampleLayout::Tile);
+    }
+
+    #[test]
+    fn expands_env_variables() {
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(
+            interpolate_env("${HOME}/.local/bin/bar ${").unwrap(),
+            format!("{home}/.local/bin/bar ${{")
+        );
+    }
+
+    #[test]
+    fn rejects_unset_env_variables() {
+        let err = interpolate_env("echo ${FHT_CONFIG_SURELY_UNSET_VAR} $HOME").unwrap_err();
+        assert!(matches!(
+            err,
+            Error::UndefinedEnvVar(name) if name == "FHT_CONFIG_SURELY_UNSET_VAR"
+        ));
+    }
+}
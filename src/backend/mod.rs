@@ -1,7 +1,11 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
 use smithay::output::Output;
 use smithay::utils::{Monotonic, Time};
 
 use crate::state::Fht;
+use crate::utils::fps::{Fps, RenderStats};
 
 #[cfg(feature = "udev_backend")]
 pub mod udev;
@@ -56,6 +60,31 @@ impl Backend {
         unreachable!("Tried to get udev backend data on non-udev backend!");
     }
 
+    /// Get the frame timing statistics of this output, if the backend has a surface for it.
+    pub fn render_stats(&self, output: &Output) -> Option<RenderStats> {
+        let fps: &Fps = match self {
+            #[cfg(feature = "x11_backend")]
+            Self::X11(data) => data.fps_for(output)?,
+            #[cfg(feature = "udev_backend")]
+            Self::Udev(data) => data.fps_for(output)?,
+        };
+
+        // Output refresh rate is in mHz.
+        let refresh = output.current_mode().map_or(60_000, |mode| mode.refresh);
+        let refresh_interval = Duration::from_secs_f64(1_000.0 / refresh.max(1) as f64);
+        Some(fps.stats(refresh_interval))
+    }
+
+    /// Get the connectors that can be leased, by name, and whether they are currently leased.
+    pub fn lease_connectors(&self) -> HashMap<String, bool> {
+        match self {
+            #[cfg(feature = "x11_backend")]
+            Self::X11(_) => HashMap::new(),
+            #[cfg(feature = "udev_backend")]
+            Self::Udev(data) => data.lease_connectors(),
+        }
+    }
+
     /// Request the backend to schedule a next frame for this output.
     ///
     /// The backend is free to oblige or discard your request, based on internal state like Vblank
@@ -48,7 +48,7 @@ use smithay::reexports::drm::control::connector::{
     self, Handle as ConnectorHandle, Info as ConnectorInfo,
 };
 use smithay::reexports::drm::control::crtc::Handle as CrtcHandle;
-use smithay::reexports::drm::control::ModeTypeFlags;
+use smithay::reexports::drm::control::{Device as _, ModeTypeFlags};
 use smithay::reexports::drm::Device as _;
 use smithay::reexports::gbm::{BufferObject, Device as GbmDevice};
 use smithay::reexports::input::{DeviceCapability, Libinput};
@@ -188,23 +188,13 @@ impl UdevData {
                         device.led_update(led_state.into());
                     }
 
-                    let device_config = CONFIG
-                        .input
-                        .per_device
-                        .get(device.name())
-                        .or_else(|| CONFIG.input.per_device.get(device.sysname()));
-                    let mouse_config =
-                        device_config.map_or_else(|| &CONFIG.input.mouse, |cfg| &cfg.mouse);
-                    let keyboard_config =
-                        device_config.map_or_else(|| &CONFIG.input.keyboard, |cfg| &cfg.keyboard);
-                    let disabled = device_config.map_or(false, |cfg| cfg.disable);
-
-                    crate::config::apply_libinput_settings(
-                        device,
-                        mouse_config,
-                        keyboard_config,
-                        disabled,
-                    );
+                    crate::config::apply_device_config(device);
+                    // The focused window might want raw pointer input.
+                    if state.fht.input_overrides.disable_pointer_accel
+                        && device.has_capability(DeviceCapability::Pointer)
+                    {
+                        crate::input::disable_pointer_accel(device);
+                    }
 
                     state.fht.devices.push(device.clone());
                 } else if let InputEvent::DeviceRemoved { ref device } = event {
@@ -255,6 +245,22 @@ impl UdevData {
                         }
                     }
 
+                    // Leases got revoked when pausing, drive the leased connectors again.
+                    let device_nodes = state
+                        .backend
+                        .udev()
+                        .devices
+                        .keys()
+                        .copied()
+                        .collect::<Vec<_>>();
+                    for device_node in device_nodes {
+                        state.backend.udev().resume_leased_connectors(
+                            device_node,
+                            |_| true,
+                            &mut state.fht,
+                        );
+                    }
+
                     for output in state.fht.outputs() {
                         OutputState::get(output).render_state.queue()
                     }
@@ -502,6 +508,9 @@ impl UdevData {
             Device {
                 surfaces: HashMap::new(),
                 non_desktop_connectors: Vec::new(),
+                desktop_lease_connectors: Vec::new(),
+                pending_lease_connectors: Vec::new(),
+                leased_connectors: Vec::new(),
                 lease_state: DrmLeaseState::new::<State>(&fht.display_handle, &device_node)
                     .map_err(|err| {
                         warn!(?err, ?device_node, "Failed to initialize DRM lease state!");
@@ -629,12 +638,9 @@ impl UdevData {
             .dmabuf_render_formats()
             .clone();
 
-        let output_name = format!(
-            "{}-{}",
-            connector.interface().as_str(),
-            connector.interface_id()
-        );
+        let output_name = connector_name(&connector);
         info!(?crtc, ?output_name, "Trying to setup connector.");
+        let allow_lease = CONFIG.allow_lease_for(&output_name);
 
         let non_desktop =
             match drm_utils::get_property_val(&device.drm, connector.handle(), "non-desktop") {
@@ -650,6 +656,14 @@ impl UdevData {
             .unwrap_or_else(|| ("Unknown".into(), "Unknown".into()));
 
         if non_desktop {
+            if allow_lease == Some(false) {
+                info!(
+                    connector_name = output_name,
+                    "Leasing is disabled for non-desktop connector, ignoring it!"
+                );
+                return Ok(());
+            }
+
             info!(
                 connector_name = output_name,
                 "Setting up connector for leasing!"
@@ -718,7 +732,7 @@ impl UdevData {
             GbmBufferFlags::RENDERING | GbmBufferFlags::SCANOUT,
         );
 
-        let color_formats = if CONFIG.renderer.disable_10bit {
+        let color_formats = if CONFIG.disable_10bit_for(&output.name()) {
             SUPPORTED_FORMATS_8BIT_ONLY
         } else {
             SUPPORTED_FORMATS
@@ -763,6 +777,11 @@ impl UdevData {
             Some(device.gbm.clone()),
         )
         .context("Failed to create DRM compositor for surface!")?;
+        let bit_depth = match compositor.format() {
+            Fourcc::Abgr2101010 | Fourcc::Argb2101010 => 10,
+            _ => 8,
+        };
+        fht.set_ipc_output_bit_depth(&output, bit_depth);
 
         // We only render on one primary gpu, so we don't have to manage different feedbacks based
         // on render nodes.
@@ -787,6 +806,26 @@ impl UdevData {
 
         device.surfaces.insert(crtc, surface);
 
+        // When resuming after a lease, the connector is still registered for leasing.
+        if allow_lease == Some(true)
+            && !device
+                .desktop_lease_connectors
+                .iter()
+                .any(|(info, _)| info.handle() == connector.handle())
+        {
+            if let Some(leasing_state) = device.lease_state.as_mut() {
+                let physical_properties = output.physical_properties();
+                leasing_state.add_connector::<State>(
+                    connector.handle(),
+                    output.name(),
+                    format!("{}-{}", physical_properties.make, physical_properties.model),
+                );
+            }
+            device
+                .desktop_lease_connectors
+                .push((connector.clone(), crtc));
+        }
+
         // if let Err(err) = self.schedule_render(&output, Duration::ZERO, &fht.loop_handle) {
         //     error!(?err, "Failed to schedule initial render for surface!");
         // };
@@ -810,6 +849,21 @@ impl UdevData {
             return Ok(());
         };
 
+        let pending_pos = device
+            .pending_lease_connectors
+            .iter()
+            .position(|handle| *handle == connector.handle());
+        if let Some(pos) = pending_pos {
+            let _ = device.pending_lease_connectors.remove(pos);
+        }
+        let leased_pos = device
+            .leased_connectors
+            .iter()
+            .position(|(handle, _)| *handle == connector.handle());
+        if let Some(pos) = leased_pos {
+            let _ = device.leased_connectors.remove(pos);
+        }
+
         if let Some(pos) = device
             .non_desktop_connectors
             .iter()
@@ -823,31 +877,108 @@ impl UdevData {
             return Ok(());
         }
 
-        let Some(surface) = device.surfaces.remove(&crtc) else {
-            panic!("Tried to remove a non-existant surface!")
-        };
+        if let Some(pos) = device
+            .desktop_lease_connectors
+            .iter()
+            .position(|(info, _)| info.handle() == connector.handle())
+        {
+            let _ = device.desktop_lease_connectors.remove(pos);
+            if let Some(leasing_state) = device.lease_state.as_mut() {
+                leasing_state.withdraw_connector(connector.handle());
+            }
+        }
 
-        // Remove and disable output.
-        let global = surface.output_global;
-        fht.display_handle.disable_global::<State>(global.clone());
-        let output_clone = surface.output.clone();
-        fht.loop_handle
-            .insert_source(
-                Timer::from_duration(Duration::from_secs(10)),
-                move |_time, _, state| {
-                    state
-                        .fht
-                        .display_handle
-                        .remove_global::<State>(global.clone());
-                    state.fht.remove_output(&output_clone);
-                    TimeoutAction::Drop
-                },
-            )
-            .expect("Failed to insert output global removal timer!");
+        if pending_pos.is_some() || leased_pos.is_some() {
+            // We don't drive leased connectors, so there's no surface to remove.
+            return Ok(());
+        }
+
+        if !device.remove_surface(crtc, fht) {
+            // Non-desktop connectors that can't be leased are ignored and have no surface.
+            debug!(?crtc, "No surface to remove for connector.");
+        }
 
         Ok(())
     }
 
+    /// Drive again the leased desktop connectors of this device whose lease ID matches `filter`.
+    ///
+    /// The lease ID is [`None`] for connectors whose lease didn't get created yet.
+    pub fn resume_leased_connectors(
+        &mut self,
+        device_node: DrmNode,
+        filter: impl Fn(Option<u32>) -> bool,
+        fht: &mut Fht,
+    ) {
+        let Some(device) = self.devices.get_mut(&device_node) else {
+            return;
+        };
+
+        let mut resumed = vec![];
+        if filter(None) {
+            resumed.append(&mut device.pending_lease_connectors);
+        }
+        let (ended, leased) = std::mem::take(&mut device.leased_connectors)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(_, lease_id)| filter(Some(*lease_id)));
+        device.leased_connectors = leased;
+        resumed.extend(ended.into_iter().map(|(handle, _)| handle));
+
+        let connectors = resumed
+            .into_iter()
+            .filter_map(|handle| {
+                device
+                    .desktop_lease_connectors
+                    .iter()
+                    .find(|(info, _)| info.handle() == handle)
+                    .cloned()
+            })
+            .collect::<Vec<_>>();
+
+        for (connector, crtc) in connectors {
+            info!(?crtc, "Lease ended, resuming connector.");
+            if let Err(err) = self.connector_connected(device_node, connector, crtc, fht) {
+                error!(?crtc, ?err, "Failed to resume leased connector!");
+            }
+        }
+    }
+
+    /// Get the names of the connectors that can be leased, and whether they are currently leased.
+    pub fn lease_connectors(&self) -> HashMap<String, bool> {
+        self.devices
+            .values()
+            .flat_map(|device| {
+                device
+                    .non_desktop_connectors
+                    .iter()
+                    .map(|(handle, _)| *handle)
+                    .chain(
+                        device
+                            .desktop_lease_connectors
+                            .iter()
+                            .map(|(info, _)| info.handle()),
+                    )
+                    .filter_map(|handle| {
+                        let info = device.drm.get_connector(handle, false).ok()?;
+                        let leased = device
+                            .leased_connectors
+                            .iter()
+                            .any(|(leased, _)| *leased == handle);
+                        Some((connector_name(&info), leased))
+                    })
+            })
+            .collect()
+    }
+
+    /// Get the [`Fps`] tracker of the [`Surface`] associated with this [`Output`], if any.
+    pub fn fps_for(&self, output: &Output) -> Option<&Fps> {
+        self.devices
+            .values()
+            .flat_map(|device| device.surfaces.values())
+            .find(|surface| surface.output == *output)
+            .map(|surface| &surface.fps)
+    }
+
     /// Request the backend to schedule a next frame for this output.
     #[profiling::function]
     pub fn render(
@@ -896,9 +1027,9 @@ impl UdevData {
             surface.fps.elements();
 
             // To render damage we just use solid color elements,
-            let damage_elements = CONFIG
-                .renderer
-                .damage_color
+            let damage_elements = fht
+                .debug_overrides
+                .damage_color()
                 .map(|damage_color| {
                     let mut state = OutputState::get(output);
                     draw_damage(&mut state.damage_tracker, &render_elements, damage_color)
@@ -920,7 +1051,7 @@ impl UdevData {
             .render_frame(
                 &mut renderer,
                 &output_elements_result.render_elements,
-                [0.1, 0.1, 0.1, 1.0],
+                CONFIG.background_for(&output.name()),
             )
             .map_err(|err| match err {
                 RenderFrameError::PrepareFrame(err) => SwapBuffersError::from(err),
@@ -952,6 +1083,8 @@ impl UdevData {
 
                 // wlr-screencopy have to be rendered whether we damaged or not.
                 self::render_screencopy(&mut renderer, surface, &res, fht.loop_handle.clone());
+                // Same for window thumbnails requested through IPC.
+                fht.render_thumbnails(output, &mut renderer);
 
                 if !res.is_empty {
                     let presentation_feedbacks =
@@ -1178,6 +1311,14 @@ pub struct Device {
     surfaces: HashMap<CrtcHandle, Surface>,
     /// Non-desktop connectors of this device.
     pub non_desktop_connectors: Vec<(ConnectorHandle, CrtcHandle)>,
+    /// Desktop connectors of this device that can also be leased, see `outputs.allow_lease`.
+    ///
+    /// These get driven like other desktop connectors while they are not leased.
+    pub desktop_lease_connectors: Vec<(ConnectorInfo, CrtcHandle)>,
+    /// Connectors of this device we stopped driving for a lease that is getting created.
+    pub pending_lease_connectors: Vec<ConnectorHandle>,
+    /// Connectors of this device that are currently leased, with the ID of their lease.
+    pub leased_connectors: Vec<(ConnectorHandle, u32)>,
     /// wp_drm_lease_device_v1 global.
     pub lease_state: Option<DrmLeaseState>,
     /// Active leases managed by [`DrmLeaseState`]
@@ -1195,6 +1336,69 @@ pub struct Device {
     drm_registration_token: RegistrationToken,
 }
 
+impl Device {
+    /// Remove the [`Surface`] of this CRTC and disable its output.
+    ///
+    /// Returns whether there was a surface to remove.
+    pub fn remove_surface(&mut self, crtc: CrtcHandle, fht: &mut Fht) -> bool {
+        let Some(surface) = self.surfaces.remove(&crtc) else {
+            return false;
+        };
+
+        // Remove and disable output.
+        fht.set_output_enabled(&surface.output, false);
+        let global = surface.output_global;
+        fht.display_handle.disable_global::<State>(global.clone());
+        let output_clone = surface.output.clone();
+        fht.loop_handle
+            .insert_source(
+                Timer::from_duration(Duration::from_secs(10)),
+                move |_time, _, state| {
+                    state
+                        .fht
+                        .display_handle
+                        .remove_global::<State>(global.clone());
+                    state.fht.remove_output(&output_clone);
+                    TimeoutAction::Drop
+                },
+            )
+            .expect("Failed to insert output global removal timer!");
+
+        true
+    }
+
+    /// Stop driving the [`Surface`] of this CRTC so that its connector can be leased.
+    ///
+    /// Unlike [`Device::remove_surface`], the output gets removed right away since it won't come
+    /// back before the lease ends, only the removal of its global gets delayed.
+    pub fn remove_surface_for_lease(&mut self, crtc: CrtcHandle, fht: &mut Fht) {
+        let Some(surface) = self.surfaces.remove(&crtc) else {
+            return;
+        };
+
+        let global = surface.output_global;
+        fht.display_handle.disable_global::<State>(global.clone());
+        fht.remove_output(&surface.output);
+        fht.loop_handle
+            .insert_source(
+                Timer::from_duration(Duration::from_secs(10)),
+                move |_time, _, state| {
+                    state
+                        .fht
+                        .display_handle
+                        .remove_global::<State>(global.clone());
+                    TimeoutAction::Drop
+                },
+            )
+            .expect("Failed to insert output global removal timer!");
+    }
+
+    /// Whether this CRTC is driven by a [`Surface`].
+    pub fn has_surface(&self, crtc: CrtcHandle) -> bool {
+        self.surfaces.contains_key(&crtc)
+    }
+}
+
 /// A single DRM surface.
 ///
 /// This is the backend structure associated with every [`Output`]
@@ -1227,6 +1431,15 @@ pub type GbmDrmCompositor = DrmCompositor<
     DrmDeviceFd,
 >;
 
+/// Get the name of the output associated with this connector, for example `eDP-1`.
+fn connector_name(connector: &ConnectorInfo) -> String {
+    format!(
+        "{}-{}",
+        connector.interface().as_str(),
+        connector.interface_id()
+    )
+}
+
 /// Get the surface dmabuf feedback with the primary_gpu and render_node.
 fn get_surface_dmabuf_feedback(
     primary_gpu: DrmNode,
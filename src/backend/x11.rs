@@ -24,6 +24,7 @@ use smithay::wayland::dmabuf::{
     DmabufFeedback, DmabufFeedbackBuilder, DmabufGlobal, ImportNotifier,
 };
 
+use crate::config::CONFIG;
 use crate::renderer::shaders::Shaders;
 use crate::state::{Fht, OutputState, RenderState, State};
 use crate::utils::fps::Fps;
@@ -266,6 +267,14 @@ impl X11Data {
         Ok(())
     }
 
+    /// Get the [`Fps`] tracker of the [`Surface`] associated with this [`Output`], if any.
+    pub fn fps_for(&self, output: &Output) -> Option<&Fps> {
+        self.surfaces
+            .values()
+            .find(|surface| surface.output == *output)
+            .map(|surface| &surface.fps)
+    }
+
     /// Render a given [`Output`], if an associated [`Surface`] is found for it.
     #[profiling::function]
     pub fn render(
@@ -295,7 +304,7 @@ impl X11Data {
             &mut self.renderer,
             buffer_age as usize,
             &output_elements_result.render_elements,
-            [0.1, 0.1, 0.1, 1.0],
+            CONFIG.background_for(&output.name()),
         );
 
         surface.fps.render();
@@ -318,6 +327,9 @@ impl X11Data {
                     .submit()
                     .context("Failed to submit buffer to X11Surface!")?;
                 surface.fps.displayed();
+                // Window thumbnails requested through IPC have to be rendered whether we damaged
+                // or not.
+                state.render_thumbnails(output, &mut self.renderer);
                 if damage.is_some() {
                     let mut output_presentation_feedback =
                         state.take_presentation_feedback(&surface.output, &states);
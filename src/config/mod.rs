@@ -6,15 +6,36 @@ use smithay::reexports::input::{Device, DeviceCapability, SendEventsMode};
 
 #[allow(unused_imports)]
 pub use self::types::{
-    AnimationConfig, BorderConfig, ColorConfig, CompositorConfig, CursorConfig, GeneralConfig,
-    InputConfig, InsertWindowStrategy, KeyboardConfig, MouseConfig, PerDeviceInputConfig,
-    WindowMapSettings, WindowRulePattern, WorkspaceSwitchAnimationConfig,
-    WorkspaceSwitchAnimationDirection,
+    AnimationConfig, BorderConfig, BorderOverrides, ColorConfig, CompositorConfig, CornerRadius,
+    CursorConfig, GeneralConfig, InputConfig, InsertWindowStrategy, KeyboardConfig, MouseConfig,
+    OutputConfig, PerDeviceInputConfig, TouchConfig, WindowInputOverrides, WindowMapSettings,
+    WindowRulePattern, WorkspaceSwitchAnimationConfig, WorkspaceSwitchAnimationDirection,
 };
+use crate::shell::KeyboardFocusTarget;
 use crate::state::{OutputState, State};
 
 pub static CONFIG: ConfigWrapper<CompositorConfig> = ConfigWrapper::new();
 
+/// Load the configuration file and print suggestions to fix mistakes in it.
+///
+/// This is used by the `--lint-config` command line flag.
+pub fn lint_config() -> anyhow::Result<()> {
+    let config =
+        CompositorConfig::load().map_err(|err| anyhow::anyhow!("Failed to load config! {err}"))?;
+    let suggestions = config.lint();
+    if suggestions.is_empty() {
+        println!(
+            "No issues found in {}",
+            CompositorConfig::get_path().display()
+        );
+    }
+    for suggestion in suggestions {
+        println!("{suggestion}");
+    }
+
+    Ok(())
+}
+
 pub fn init_config_file_watcher(
     loop_handle: &LoopHandle<'static, State>,
 ) -> anyhow::Result<RegistrationToken> {
@@ -25,7 +46,9 @@ pub fn init_config_file_watcher(
             let calloop::channel::Event::Msg(()) = event else {
                 return;
             };
-            state.reload_config();
+            if let Err(err) = state.reload_config() {
+                warn!(?err, "Failed to reload config!");
+            }
         })
         .map_err(|err| anyhow::anyhow!("Failed to insert config file watcher source! {err}"))?;
 
@@ -53,25 +76,45 @@ pub fn init_config_file_watcher(
     Ok(watcher_token)
 }
 
+/// A summary of a successful configuration reload.
+#[derive(Debug, Clone, Copy)]
+pub struct ConfigReloadSummary {
+    /// The number of window rules in the new configuration.
+    ///
+    /// Rules only apply to windows mapped after the reload.
+    pub rules: usize,
+    /// The number of outputs that got reconfigured.
+    pub outputs: usize,
+}
+
 impl State {
+    /// Reload the configuration file.
+    ///
+    /// If the new configuration fails to load, the current one is kept and the error is returned.
+    /// Windows are never removed by a reload, and the focused window stays focused.
     #[profiling::function]
-    pub fn reload_config(&mut self) {
+    pub fn reload_config(&mut self) -> anyhow::Result<ConfigReloadSummary> {
         let new_config = match CompositorConfig::load() {
             Ok(config) => config,
             Err(err) => {
+                let message = err.to_string();
                 self.fht.last_config_error = Some(anyhow::anyhow!(err));
-                return;
+                anyhow::bail!(message);
             }
         };
 
         if new_config.general.layouts.len() == 0 {
             self.fht.last_config_error =
                 Some(anyhow::anyhow!("You have to specify at least one layout!"));
-            return;
+            anyhow::bail!("You have to specify at least one layout!");
         }
 
+        let focused = self.fht.focus_state.focus_target.clone();
         let old_config = CONFIG.clone();
         CONFIG.set(new_config);
+        self.fht.debug_overrides = Default::default();
+        self.fht.focus_follows_mouse_override = None;
+        self.fht.primary_output_override = None;
 
         // the [`CursorThemeManager`] automatically checks for changes.
         self.fht.cursor_theme_manager.reload();
@@ -80,8 +123,18 @@ impl State {
             .for_each(|(_, wset)| wset.reload_config());
 
         let outputs = self.fht.outputs().cloned().collect::<Vec<_>>();
+        let summary = ConfigReloadSummary {
+            rules: CONFIG.rules.len(),
+            outputs: outputs.len(),
+        };
         for output in outputs {
             self.fht.output_resized(&output);
+            self.fht.update_ipc_output_alias(&output);
+        }
+        self.fht.update_ipc_primary_output();
+
+        if CONFIG.decoration != old_config.decoration {
+            self.advertise_decorations();
         }
 
         if CONFIG.input.keyboard != old_config.input.keyboard {
@@ -93,21 +146,22 @@ impl State {
             {
                 error!(?err, "Failed to update keyboard xkb configuration!");
             }
+            self.update_keyboard_layout();
         }
 
         for device in &mut self.fht.devices {
-            let device_config = CONFIG
-                .input
-                .per_device
-                .get(device.name())
-                .or_else(|| CONFIG.input.per_device.get(device.sysname()));
-
-            let mouse_config = device_config.map_or_else(|| &CONFIG.input.mouse, |cfg| &cfg.mouse);
-            let keyboard_config =
-                device_config.map_or_else(|| &CONFIG.input.keyboard, |cfg| &cfg.keyboard);
-            let disabled = device_config.map_or(false, |cfg| cfg.disable);
-
-            apply_libinput_settings(device, mouse_config, keyboard_config, disabled);
+            apply_device_config(device);
+        }
+        // The device settings got reset, restoring the focus below applies the overrides again.
+        self.fht.input_overrides = WindowInputOverrides::default();
+
+        // Rearranging the workspaces can move the focus around, give it back to the window that
+        // had it before reloading.
+        if let Some(KeyboardFocusTarget::Window(window)) = focused
+            && let Some(workspace) = self.fht.ws_mut_for(&window)
+        {
+            workspace.focus_element(&window);
+            self.set_focus_target(Some(window.into()));
         }
 
         // I assume that if you have gone this far the config has reloaded sucessfully
@@ -116,13 +170,36 @@ impl State {
         for output in self.fht.outputs() {
             OutputState::get(output).render_state.queue();
         }
+
+        Ok(summary)
     }
 }
 
+/// Apply the input configuration of this device, using its per-device configuration if any.
+pub fn apply_device_config(device: &mut Device) {
+    let device_config = CONFIG
+        .input
+        .per_device_config(device.name(), device.sysname());
+
+    let mouse_config = device_config.map_or_else(|| &CONFIG.input.mouse, |cfg| &cfg.mouse);
+    let keyboard_config = device_config.map_or_else(|| &CONFIG.input.keyboard, |cfg| &cfg.keyboard);
+    let touch_config = CONFIG.input.touch_config(device.name(), device.sysname());
+    let disabled = device_config.map_or(false, |cfg| cfg.disable);
+
+    apply_libinput_settings(
+        device,
+        mouse_config,
+        keyboard_config,
+        &touch_config,
+        disabled,
+    );
+}
+
 pub fn apply_libinput_settings(
     device: &mut Device,
     mouse_config: &MouseConfig,
     _: &KeyboardConfig,
+    touch_config: &TouchConfig,
     disabled: bool,
 ) {
     let _ = device.config_send_events_set_mode(if disabled {
@@ -148,4 +225,13 @@ pub fn apply_libinput_settings(
             let _ = device.config_tap_set_button_map(mouse_config.tap_to_click_behaviour);
         }
     }
+
+    if device.has_capability(DeviceCapability::Touch) {
+        if let Some(matrix) = touch_config
+            .calibration_matrix
+            .or_else(|| device.config_calibration_default_matrix())
+        {
+            let _ = device.config_calibration_set_matrix(matrix);
+        }
+    }
 }
@@ -1,14 +1,14 @@
 use colors_transform::{AlphaColor, Color, Hsl, Rgb};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-pub use self::border::BorderConfig;
+pub use self::border::{BorderConfig, BorderOverrides, CornerRadius};
 pub use self::color::ColorConfig;
 
 const fn default_window_opacity() -> f32 {
     1.0
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DecorationConfig {
     /// The configuration for the border around the windows.
     pub border: BorderConfig,
@@ -25,6 +25,13 @@ pub struct DecorationConfig {
     #[serde(default = "default_window_opacity")]
     pub normal_window_opacity: f32,
 
+    /// An additional opacity modifier used to dim inactive windows.
+    ///
+    /// This is multiplied with `normal_window_opacity`, and clamped inside `0.0..=1.0`. Leaving
+    /// this unset keeps inactive windows at the same opacity as before.
+    #[serde(default, alias = "inactive_opacity")]
+    pub inactive_window_opacity: Option<f32>,
+
     /// Should we allow clients to draw their own decorations.
     ///
     /// Basically allow what is called CSD, or client side decorations.
@@ -45,11 +52,22 @@ impl Default for DecorationConfig {
             border: Default::default(),
             focused_window_opacity: default_window_opacity(),
             normal_window_opacity: default_window_opacity(),
+            inactive_window_opacity: None,
             allow_csd: false,
         }
     }
 }
 
+impl DecorationConfig {
+    /// Get the final opacity modifier of unfocused windows.
+    pub fn unfocused_window_opacity(&self) -> f32 {
+        let inactive_opacity = self
+            .inactive_window_opacity
+            .map_or(1.0, |opacity| opacity.clamp(0.0, 1.0));
+        self.normal_window_opacity * inactive_opacity
+    }
+}
+
 mod border {
     use super::*;
 
@@ -57,11 +75,46 @@ mod border {
         2
     }
 
-    const fn default_radius() -> f32 {
-        10.0
+    const fn default_radius() -> CornerRadius {
+        CornerRadius([10.0; 4])
+    }
+
+    /// The radius of each corner of a border, in the order top-left, top-right, bottom-right,
+    /// bottom-left.
+    ///
+    /// In the configuration, you can either give a single value used for all the corners, or an
+    /// array with the four values.
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+    #[serde(transparent)]
+    pub struct CornerRadius(pub [f32; 4]);
+
+    impl<'de> Deserialize<'de> for CornerRadius {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            #[derive(Deserialize)]
+            #[serde(untagged)]
+            enum Repr {
+                Single(f32),
+                PerCorner([f32; 4]),
+            }
+
+            let radii = match Repr::deserialize(deserializer)? {
+                Repr::Single(radius) => [radius; 4],
+                Repr::PerCorner(radii) => radii,
+            };
+
+            // The thickness is an u8, so negative values are already rejected, not the radius
+            // though.
+            if let Some(radius) = radii.iter().find(|r| r.is_nan() || **r < 0.0) {
+                return Err(<D::Error as serde::de::Error>::custom(format!(
+                    "Invalid border radius {radius}! It must be a positive number."
+                )));
+            }
+
+            Ok(Self(radii))
+        }
     }
 
-    #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+    #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
     pub struct BorderConfig {
         /// The border color for the focused window.
         pub focused_color: ColorConfig,
@@ -69,13 +122,19 @@ mod border {
         /// The border color for the non-focused window(s).
         pub normal_color: ColorConfig,
 
+        /// The border color for windows requesting attention, until they get focused.
+        ///
+        /// If not set, urgent windows use the normal colors.
+        #[serde(default)]
+        pub urgent_color: Option<ColorConfig>,
+
         /// The thickness of the border.
         #[serde(default = "default_thickness")]
         pub thickness: u8,
 
         /// The radius of the border.
         #[serde(default = "default_radius")]
-        pub radius: f32,
+        pub radius: CornerRadius,
     }
 
     impl Default for BorderConfig {
@@ -83,18 +142,49 @@ mod border {
             Self {
                 focused_color: ColorConfig::Solid([1.0, 0.0, 0.0, 1.0]),
                 normal_color: ColorConfig::Solid([0.5, 0.5, 0.5, 0.5]),
+                urgent_color: None,
                 thickness: 2,
-                radius: 10.0,
+                radius: default_radius(),
             }
         }
     }
 
+    /// Runtime overrides applied on top of a [`BorderConfig`].
+    ///
+    /// These are set through the IPC, and get cleared when reloading the configuration.
+    #[derive(Debug, Default, Clone, Copy, PartialEq)]
+    pub struct BorderOverrides {
+        pub focused_color: Option<ColorConfig>,
+        pub normal_color: Option<ColorConfig>,
+        pub thickness: Option<u8>,
+        pub radius: Option<CornerRadius>,
+    }
+
+    impl BorderOverrides {
+        /// Merge these overrides with the given border configuration.
+        pub fn merge_with(&self, mut config: BorderConfig) -> BorderConfig {
+            if let Some(focused_color) = self.focused_color {
+                config.focused_color = focused_color;
+            }
+            if let Some(normal_color) = self.normal_color {
+                config.normal_color = normal_color;
+            }
+            if let Some(thickness) = self.thickness {
+                config.thickness = thickness;
+            }
+            if let Some(radius) = self.radius {
+                config.radius = radius;
+            }
+            config
+        }
+    }
+
     impl BorderConfig {
         /// Get the radius of the border.
         ///
         /// We subtract half_thickness to get more accurate radius with varying thicknesses
-        pub fn radius(&self) -> f32 {
-            self.radius - self.half_thickness()
+        pub fn radius(&self) -> [f32; 4] {
+            self.radius.0.map(|radius| radius - self.half_thickness())
         }
 
         /// Get the half_thickness of the border
@@ -178,3 +268,35 @@ mod color {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_border(thickness: &str, radius: &str) -> ron::error::SpannedResult<BorderConfig> {
+        ron::from_str(&format!(
+            r##"(
+                thickness: {thickness},
+                radius: {radius},
+                focused_color: Solid("#6791c9"),
+                normal_color: Solid("#44474f"),
+            )"##
+        ))
+    }
+
+    #[test]
+    fn rejects_negative_border_thickness() {
+        assert!(parse_border("-2", "8").is_err());
+    }
+
+    #[test]
+    fn accepts_zero_border_thickness() {
+        assert_eq!(parse_border("0", "8").unwrap().thickness, 0);
+    }
+
+    #[test]
+    fn rejects_negative_border_radius() {
+        assert!(parse_border("2", "-8").is_err());
+        assert!(parse_border("2", "[8, 8, -1, 8]").is_err());
+    }
+}
@@ -2,7 +2,7 @@ use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 
 pub use self::keyboard::KeyboardConfig;
-pub use self::mouse::MouseConfig;
+pub use self::mouse::{CustomAcceleration, MouseConfig};
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct InputConfig {
@@ -14,6 +14,10 @@ pub struct InputConfig {
     #[serde(default)]
     pub mouse: MouseConfig,
 
+    /// Touch screen specific settings.
+    #[serde(default)]
+    pub touch: TouchConfig,
+
     /// Per device settings.
     ///
     /// Each device config is the same as [`InputConfig`], just specific to a device.
@@ -21,11 +25,83 @@ pub struct InputConfig {
     /// As far as I know [`KeyboardConfig`] is specific to the global wl_seat object, so this won't
     /// really affect anything, so eh, [`MouseConfig`] works though.
     ///
-    /// NOTE: Having this set for a device will IGNORE any other global config.
+    /// NOTE: Having this set for a device will IGNORE any other global config, except for touch
+    /// settings, see [`InputConfig::touch_config`].
+    ///
+    /// The keys can be glob patterns, where `*` matches any number of characters and `?` a single
+    /// one, for example `"*Logitech*"`. An exact match always takes precedence, then the longest
+    /// matching pattern is used.
     #[serde(default)]
     pub per_device: IndexMap<String, PerDeviceInputConfig>,
 }
 
+impl InputConfig {
+    /// Get the per-device configuration for a device with this name and sysname, if any.
+    ///
+    /// See [`InputConfig::per_device`] for how the keys get matched.
+    pub fn per_device_config(&self, name: &str, sysname: &str) -> Option<&PerDeviceInputConfig> {
+        if let Some(config) = self
+            .per_device
+            .get(name)
+            .or_else(|| self.per_device.get(sysname))
+        {
+            return Some(config);
+        }
+
+        self.per_device
+            .iter()
+            .filter(|(pattern, _)| glob_matches(pattern, name) || glob_matches(pattern, sysname))
+            .max_by_key(|(pattern, _)| pattern.len())
+            .map(|(_, config)| config)
+    }
+
+    /// Get the touch configuration for a device with this name and sysname.
+    ///
+    /// The settings left unset in its per-device configuration fall back to the global ones, so
+    /// that a catch-all pattern like `"*"` doesn't shadow them.
+    pub fn touch_config(&self, name: &str, sysname: &str) -> TouchConfig {
+        let device_touch_config = self
+            .per_device_config(name, sysname)
+            .map(|config| &config.touch);
+        TouchConfig {
+            map_to_output: device_touch_config
+                .and_then(|config| config.map_to_output.clone())
+                .or_else(|| self.touch.map_to_output.clone()),
+            calibration_matrix: device_touch_config
+                .and_then(|config| config.calibration_matrix)
+                .or(self.touch.calibration_matrix),
+        }
+    }
+}
+
+/// Check whether `text` matches this glob `pattern`, supporting `*` and `?`.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` in the pattern, and the text position it started matching at.
+    let mut backtrack = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = backtrack {
+            // Let the last `*` eat one more character.
+            p = star_p + 1;
+            t = star_t + 1;
+            backtrack = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
 // To avoid infinite recursion
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct PerDeviceInputConfig {
@@ -42,6 +118,25 @@ pub struct PerDeviceInputConfig {
     /// Mouse specific settings for this device, if applicable.
     #[serde(default)]
     pub mouse: MouseConfig,
+
+    /// Touch screen specific settings for this device, if applicable.
+    #[serde(default)]
+    pub touch: TouchConfig,
+}
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct TouchConfig {
+    /// The name of the output touch events are mapped to.
+    ///
+    /// If unset, or if there's no output with this name, touch events are mapped to the active
+    /// output.
+    #[serde(default)]
+    pub map_to_output: Option<String>,
+
+    /// The libinput [calibration matrix](https://wayland.freedesktop.org/libinput/doc/latest/absolute-axes.html#calibration-of-absolute-devices)
+    /// to apply to the touch screen, if any.
+    #[serde(default)]
+    pub calibration_matrix: Option<[f32; 6]>,
 }
 
 mod keyboard {
@@ -168,6 +263,14 @@ mod mouse {
         #[serde(default = "default_accelspeed")]
         pub acceleration_speed: f64,
 
+        /// A libinput [custom acceleration](https://wayland.freedesktop.org/libinput/doc/latest/pointer-acceleration.html#the-custom-acceleration-profile)
+        /// function, used instead of `acceleration_profile` when set.
+        ///
+        /// WARN: The libinput bindings we use don't expose custom acceleration yet, so this
+        /// currently falls back to `acceleration_profile` with a warning.
+        #[serde(default)]
+        pub custom_acceleration: Option<CustomAcceleration>,
+
         /// Switches the left and right mouse buttons, to adapt for lefties.
         #[serde(default = "default_false")]
         pub left_handed: bool,
@@ -223,6 +326,7 @@ mod mouse {
             Self {
                 acceleration_profile: default_accelprofile(),
                 acceleration_speed: default_accelspeed(),
+                custom_acceleration: None,
                 left_handed: default_false(),
                 scroll_method: default_scrollmethod(),
                 natural_scrolling: default_false(),
@@ -235,6 +339,41 @@ mod mouse {
         }
     }
 
+    /// A custom acceleration function, defined by points sampled at a fixed step.
+    ///
+    /// See the libinput documentation for how the points are interpreted.
+    #[derive(Debug, Clone, Serialize)]
+    pub struct CustomAcceleration {
+        /// The distance between each point, in device units per millisecond.
+        pub step: f64,
+        /// The points of the acceleration function.
+        pub points: Vec<f64>,
+    }
+
+    impl<'de> Deserialize<'de> for CustomAcceleration {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            #[derive(Deserialize)]
+            struct Raw {
+                step: f64,
+                points: Vec<f64>,
+            }
+
+            let Raw { step, points } = Raw::deserialize(deserializer)?;
+            if !step.is_finite() || step <= 0.0 {
+                return Err(<D::Error as serde::de::Error>::custom(
+                    "Custom acceleration step must be positive!",
+                ));
+            }
+            if points.is_empty() {
+                return Err(<D::Error as serde::de::Error>::custom(
+                    "Custom acceleration needs at least one point!",
+                ));
+            }
+
+            Ok(Self { step, points })
+        }
+    }
+
     mod ser {
         use serde::{Deserialize, Deserializer, Serializer};
         use smithay::reexports::input::{AccelProfile, ScrollMethod, TapButtonMap};
@@ -305,3 +444,79 @@ mod mouse {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn input_config(patterns: &[&str]) -> InputConfig {
+        let per_device = patterns
+            .iter()
+            .map(|pattern| {
+                let mut config = PerDeviceInputConfig::default();
+                // Tag each config with its pattern, to know which one got picked.
+                config.touch.map_to_output = Some(pattern.to_string());
+                (pattern.to_string(), config)
+            })
+            .collect();
+        InputConfig {
+            per_device,
+            ..Default::default()
+        }
+    }
+
+    fn resolved<'a>(config: &'a InputConfig, name: &str) -> Option<&'a str> {
+        config
+            .per_device_config(name, "event0")
+            .and_then(|config| config.touch.map_to_output.as_deref())
+    }
+
+    #[test]
+    fn per_device_exact_match_has_precedence() {
+        let config = input_config(&["*Logitech*", "Logitech G Pro", "*"]);
+        assert_eq!(resolved(&config, "Logitech G Pro"), Some("Logitech G Pro"));
+    }
+
+    #[test]
+    fn per_device_longest_glob_has_precedence() {
+        let config = input_config(&["*", "*Logitech*", "Logitech G ??? *"]);
+        assert_eq!(
+            resolved(&config, "Logitech G Pro Wireless"),
+            Some("Logitech G ??? *")
+        );
+        assert_eq!(resolved(&config, "Logitech MX Master"), Some("*Logitech*"));
+        assert_eq!(resolved(&config, "Some Keyboard"), Some("*"));
+    }
+
+    #[test]
+    fn per_device_no_match() {
+        let config = input_config(&["*Logitech*", "Wacom?"]);
+        assert_eq!(resolved(&config, "Wacom Intuos"), None);
+    }
+
+    #[test]
+    fn touch_config_falls_back_to_global() {
+        let mut config = input_config(&["event5"]);
+        config
+            .per_device
+            .insert("*".to_string(), Default::default());
+        config.touch.map_to_output = Some("eDP-1".to_string());
+        config.touch.calibration_matrix = Some([1., 0., 0., 0., 1., 0.]);
+
+        // A matching entry without touch settings doesn't shadow the global ones.
+        let touch_config = config.touch_config("Some Touchscreen", "event0");
+        assert_eq!(touch_config.map_to_output.as_deref(), Some("eDP-1"));
+        assert_eq!(
+            touch_config.calibration_matrix,
+            Some([1., 0., 0., 0., 1., 0.])
+        );
+
+        // Matching by sysname, only the set fields get overridden.
+        let touch_config = config.touch_config("Some Touchscreen", "event5");
+        assert_eq!(touch_config.map_to_output.as_deref(), Some("event5"));
+        assert_eq!(
+            touch_config.calibration_matrix,
+            Some([1., 0., 0., 0., 1., 0.])
+        );
+    }
+}
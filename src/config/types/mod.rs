@@ -1,25 +1,44 @@
 mod animation;
 mod decoration;
 mod input;
+mod output;
 mod rules;
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use indexmap::IndexMap;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use smithay::input::keyboard::Keysym;
 use smithay::reexports::rustix::path::Arg;
 
 pub use self::animation::*;
 pub use self::decoration::*;
 pub use self::input::*;
+pub use self::output::*;
 pub use self::rules::*;
-use crate::input::{KeyAction, KeyPattern, MouseAction, MousePattern};
+use crate::input::{
+    FhtModifiersState, FhtMouseButton, KeyAction, KeyPattern, MouseAction, MousePattern,
+    ScreenEdge, SwitchTrigger,
+};
 use crate::shell::workspaces::WorkspaceLayout;
 
 const fn default_true() -> bool {
     true
 }
 
+const fn default_background() -> ColorConfig {
+    ColorConfig::Solid([0.1, 0.1, 0.1, 1.0])
+}
+
+const fn default_screen_edge_size() -> u32 {
+    1
+}
+
+const fn default_headless_fallback_size() -> (i32, i32) {
+    (1920, 1080)
+}
+
 fn default_layouts() -> Vec<WorkspaceLayout> {
     vec![WorkspaceLayout::Tile {
         nmaster: 1,
@@ -31,7 +50,8 @@ fn default_layouts() -> Vec<WorkspaceLayout> {
 pub struct CompositorConfig {
     /// A list of programs to autostart
     ///
-    /// NOTE: These are evaluated using `/bin/sh`
+    /// NOTE: These are evaluated using `/bin/sh`, after expanding `${VAR}` environment variable
+    /// references.
     #[serde(default)]
     pub autostart: Vec<String>,
 
@@ -40,13 +60,29 @@ pub struct CompositorConfig {
     pub greet: bool,
 
     /// Keybinds, table of key patterns bound to key actions.
-    #[serde(default)]
+    ///
+    /// A key pattern can also list multiple keysyms sharing the same action, for example
+    /// `([SUPER], ["j", "Down"])`, these get expanded into one keybind per keysym.
+    #[serde(default, deserialize_with = "crate::input::deserialize_keybinds")]
     pub keybinds: IndexMap<KeyPattern, KeyAction>,
 
     /// Mousebinds, a table of mouse pattern bound to mouse actions.
     #[serde(default)]
     pub mousebinds: IndexMap<MousePattern, MouseAction>,
 
+    /// Switches, table of hardware switches bound to key actions.
+    ///
+    /// This lets you for example lock your session when closing the lid of your laptop.
+    #[serde(default)]
+    pub switches: HashMap<SwitchTrigger, KeyAction>,
+
+    /// Screen edges, table of output corners and edges bound to key actions.
+    ///
+    /// The action runs once when the pointer enters the corner/edge, see
+    /// `general.screen_edge_size` and `general.screen_edge_delay`.
+    #[serde(default)]
+    pub screen_edges: HashMap<ScreenEdge, KeyAction>,
+
     /// Input configuration.
     #[serde(default)]
     pub input: InputConfig,
@@ -67,9 +103,19 @@ pub struct CompositorConfig {
     #[serde(default)]
     pub rules: HashMap<Vec<WindowRulePattern>, WindowMapSettings>,
 
+    /// Default sizes of windows when they get floated, by app_id regex.
+    ///
+    /// Windows without a matching entry keep the size they had before getting floated.
+    #[serde(default)]
+    pub floating_defaults: FloatingDefaults,
+
     /// Configuration for the backend renderer.
     #[serde(default)]
     pub renderer: RenderConfig,
+
+    /// Per-output configuration, keyed by the output name (for example `eDP-1`)
+    #[serde(default)]
+    pub outputs: HashMap<String, OutputConfig>,
 }
 
 impl Default for CompositorConfig {
@@ -79,19 +125,182 @@ impl Default for CompositorConfig {
             greet: false,
             keybinds: IndexMap::new(),
             mousebinds: IndexMap::new(),
+            switches: HashMap::new(),
+            screen_edges: HashMap::new(),
             input: InputConfig::default(),
             general: GeneralConfig::default(),
             decoration: DecorationConfig::default(),
             animation: AnimationConfig::default(),
             rules: HashMap::new(),
+            floating_defaults: FloatingDefaults::default(),
             renderer: RenderConfig::default(),
+            outputs: HashMap::new(),
         }
     }
 }
 
+impl CompositorConfig {
+    /// Get the mouse action bound to a given mouse pattern.
+    ///
+    /// Explicit `mousebinds` entries always take precedence over the default mousebinds
+    /// synthesized from `general.tiling_modifier`.
+    pub fn mouse_action_for(&self, pattern: &MousePattern) -> Option<MouseAction> {
+        if let Some(action) = self.mousebinds.get(pattern) {
+            return Some(action.clone());
+        }
+
+        let tiling_modifier = self.general.tiling_modifier?;
+        if pattern.0 != tiling_modifier {
+            return None;
+        }
+
+        match pattern.1 {
+            FhtMouseButton::Left => Some(MouseAction::MoveTile),
+            _ => None,
+        }
+    }
+}
+
+impl CompositorConfig {
+    /// Get the background color of the output with this name.
+    ///
+    /// This is the output's `background` if set, falling back to `general.background`
+    pub fn background_for(&self, output_name: &str) -> [f32; 4] {
+        self.outputs
+            .get(output_name)
+            .and_then(|config| config.background)
+            .unwrap_or(self.general.background)
+            .components()
+    }
+
+    /// Look for mistakes in this configuration that don't prevent it from loading, returning a
+    /// suggestion to fix each of them.
+    pub fn lint(&self) -> Vec<String> {
+        let mut suggestions = vec![];
+
+        if self.general.layouts.is_empty() {
+            suggestions.push(
+                "general.layouts: The list is empty, add at least one layout or the config won't \
+                 reload."
+                    .to_string(),
+            );
+        }
+
+        if self.switches.contains_key(&SwitchTrigger::PowerButton) {
+            for pattern in self.keybinds.keys() {
+                if pattern.1 == Keysym::XF86_PowerOff {
+                    let pattern = ron::to_string(pattern).unwrap_or_default();
+                    suggestions.push(format!(
+                        "keybinds.{pattern}: This keybind never fires since switches.PowerButton \
+                         takes the power button, remove one of them."
+                    ));
+                }
+            }
+        }
+
+        for (patterns, settings) in &self.rules {
+            let rule = ron::to_string(patterns).unwrap_or_default();
+            if patterns.is_empty() {
+                suggestions.push(format!(
+                    "rules.{rule}: The rule has no patterns and never applies, add a pattern."
+                ));
+            }
+            if patterns.iter().any(WindowRulePattern::is_empty) {
+                suggestions.push(format!(
+                    "rules.{rule}: A pattern has no workspace, title or app_id and never matches, \
+                     add a condition to it."
+                ));
+            }
+            if let Some(workspace) = settings.workspace.filter(|idx| *idx > 8) {
+                suggestions.push(format!(
+                    "rules.{rule}.workspace: There's no workspace {workspace}, the window goes \
+                     to workspace 8. Workspace indices go from 0 to 8."
+                ));
+            }
+        }
+
+        let mut primary_outputs = self
+            .outputs
+            .iter()
+            .filter(|(_, config)| config.primary == Some(true))
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        if primary_outputs.len() > 1 {
+            primary_outputs.sort_unstable();
+            suggestions.push(format!(
+                "outputs: {} are all marked as primary, only one of them gets used. Keep \
+                 primary: true on a single output.",
+                primary_outputs.join(", ")
+            ));
+        }
+
+        suggestions
+    }
+
+    /// Whether 10-bit color formats are disabled for the output with this name.
+    ///
+    /// This is the output's `force_10bit` if set, falling back to `renderer.disable_10bit`
+    pub fn disable_10bit_for(&self, output_name: &str) -> bool {
+        self.outputs
+            .get(output_name)
+            .and_then(|config| config.force_10bit)
+            .map_or(self.renderer.disable_10bit, |force| !force)
+    }
+
+    /// Get whether the connector of the output with this name can be leased, if configured.
+    pub fn allow_lease_for(&self, output_name: &str) -> Option<bool> {
+        self.outputs
+            .get(output_name)
+            .and_then(|config| config.allow_lease)
+    }
+}
+
 impl fht_config::Config for CompositorConfig {
     const NAME: &'static str = "compositor";
     const DEFAULT_CONTENTS: &'static str = include_str!("../../../res/compositor.ron");
+
+    fn post_load(&mut self) -> Result<(), fht_config::Error> {
+        // Warn once per load, instead of every time the device settings get applied.
+        if std::iter::once(&self.input.mouse)
+            .chain(self.input.per_device.values().map(|config| &config.mouse))
+            .any(|mouse_config| mouse_config.custom_acceleration.is_some())
+        {
+            warn!("Custom acceleration isn't supported yet, using acceleration_profile instead.");
+        }
+
+        // Aliases are used in place of output names, they must not be ambiguous.
+        let mut aliases = HashSet::new();
+        for (name, output_config) in &self.outputs {
+            let Some(alias) = output_config.alias.as_ref() else {
+                continue;
+            };
+            if alias == "active"
+                || (alias != name && self.outputs.contains_key(alias))
+                || !aliases.insert(alias)
+            {
+                return Err(fht_config::Error::Parse(ron::Error::Message(format!(
+                    "outputs.{name}.alias: {alias} is already used by another output!"
+                ))));
+            }
+        }
+
+        // Expand `${VAR}` in the command lines we spawn.
+        for cmd in &mut self.autostart {
+            *cmd = fht_config::interpolate_env(cmd)?;
+        }
+        for action in self
+            .keybinds
+            .values_mut()
+            .chain(self.switches.values_mut())
+            .chain(self.screen_edges.values_mut())
+        {
+            if let KeyAction::RunCommand(cmd) = action {
+                *cmd = fht_config::interpolate_env(cmd)?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -105,10 +314,66 @@ pub struct GeneralConfig {
     #[serde(default = "default_true")]
     pub cursor_warps: bool,
 
+    /// Should the compositor warp the cursor when switching the focused output.
+    ///
+    /// The cursor goes to the focused window of that output, or its center if there's none. This
+    /// only lets you disable these warps, they never happen if `cursor_warps` is disabled.
+    #[serde(default = "default_true")]
+    pub warp_cursor_on_output_focus: bool,
+
+    /// Should the compositor warp the cursor to the center of every newly focused window.
+    ///
+    /// Unlike `cursor_warps`, this applies to every focus change, as long as the cursor isn't
+    /// already inside the window and the window center isn't covered by a layer shell.
+    #[serde(default)]
+    pub mouse_follows_focus: bool,
+
+    /// Focus the window under the pointer when it moves, without having to click it.
+    ///
+    /// Layer shells still need a click to get focused.
+    #[serde(default)]
+    pub focus_follows_mouse: bool,
+
     /// Should new windows be focused automatically
     #[serde(default = "default_true")]
     pub focus_new_windows: bool,
 
+    /// Should windows that open floating be centered in their workspace.
+    ///
+    /// The `centered` window rule overrides this.
+    #[serde(default)]
+    pub center_new_floating: bool,
+
+    /// Should we switch back to the previously active workspace when the last window of the
+    /// active workspace closes.
+    ///
+    /// This only happens if the previous workspace has windows, and never switches away from the
+    /// first workspace.
+    #[serde(default)]
+    pub switch_back_on_empty: bool,
+
+    /// Should focusing the next workspace from the last one go back to the first one, and
+    /// focusing the previous workspace from the first one go to the last one.
+    ///
+    /// The switch animation still goes in the direction you asked for.
+    #[serde(default)]
+    pub workspace_switch_wraps: bool,
+
+    /// When should clicking on a floating window raise and focus it.
+    ///
+    /// Clicks still go through to the window even if it doesn't get raised, so that you can
+    /// interact with windows behind the focused one.
+    #[serde(default)]
+    pub raise_on_click: RaiseMode,
+
+    /// Should the focused window stay focused when other windows get inserted or removed from
+    /// its workspace.
+    ///
+    /// When disabled, the focus stays at the same position in the workspace, that can be another
+    /// window.
+    #[serde(default = "default_true")]
+    pub stable_focus_on_rearrange: bool,
+
     /// How should we insert windows inside workspaces.
     #[serde(default)]
     pub insert_window_strategy: InsertWindowStrategy,
@@ -119,32 +384,113 @@ pub struct GeneralConfig {
     #[serde(default)]
     pub cursor: CursorConfig,
 
+    /// Names to give to the workspaces, by index.
+    ///
+    /// The first name goes to the first workspace of each output, the second name to the second
+    /// workspace, and so on. Workspaces without a name here stay unnamed.
+    #[serde(default)]
+    pub workspace_names: Vec<String>,
+
+    /// App-id regexes of windows that should be floating when they get mapped.
+    ///
+    /// This is evaluated before `rules`, so a rule setting `floating` for a window takes
+    /// precedence over this list.
+    #[serde(
+        default,
+        serialize_with = "rules::serialize_regexes",
+        deserialize_with = "rules::deserialize_regexes"
+    )]
+    pub always_float: Vec<Regex>,
+
     /// Workspace layouts to use.
     #[serde(default = "default_layouts")]
     pub layouts: Vec<WorkspaceLayout>,
 
+    /// A modifier used to synthesize default mousebinds.
+    ///
+    /// When set, holding these modifiers and dragging with the left mouse button moves the tile
+    /// under the cursor, without having to define the bind yourself in `mousebinds`.
+    ///
+    /// NOTE: Explicit `mousebinds` entries using the same pattern take precedence.
+    #[serde(default)]
+    pub tiling_modifier: Option<FhtModifiersState>,
+
+    /// The background color of the outputs, seen behind the windows.
+    ///
+    /// NOTE: Gradients are not supported yet, the start color is used.
+    #[serde(default = "default_background")]
+    pub background: ColorConfig,
+
     /// Useless gap added around the output edge when tiling windows.
     #[serde(default)]
     pub outer_gaps: i32,
 
+    /// Don't apply outer gaps when there's a single tiled window in the workspace.
+    ///
+    /// The window still respects the exclusive zones of layer shells.
+    #[serde(default, alias = "no_outer_gaps_when_single")]
+    pub smart_gaps: bool,
+
+    /// Don't draw the border of the window when there's a single tiled window in the workspace.
+    #[serde(default)]
+    pub smart_borders: bool,
+
     /// Useless gap added between the windows when tiling them.
     #[serde(default)]
     pub inner_gaps: i32,
+
+    /// The size of the activation zone of screen edges, in logical pixels.
+    #[serde(default = "default_screen_edge_size")]
+    pub screen_edge_size: u32,
+
+    /// How long the pointer has to stay in a screen edge before its action runs, in milliseconds.
+    #[serde(default)]
+    pub screen_edge_delay: u64,
+
+    /// Should we create a virtual output when the last output gets disconnected, instead of
+    /// quitting.
+    ///
+    /// Windows get moved to it until a real output gets connected again. This is useful with
+    /// docking stations that drop every monitor for a moment.
+    #[serde(default)]
+    pub headless_fallback: bool,
+
+    /// The size of the virtual output created with `headless_fallback`.
+    #[serde(default = "default_headless_fallback_size")]
+    pub headless_fallback_size: (i32, i32),
 }
 
 impl Default for GeneralConfig {
     fn default() -> Self {
         Self {
             cursor_warps: true,
+            warp_cursor_on_output_focus: true,
+            mouse_follows_focus: false,
+            focus_follows_mouse: false,
             focus_new_windows: true,
+            center_new_floating: false,
+            switch_back_on_empty: false,
+            workspace_switch_wraps: false,
+            stable_focus_on_rearrange: true,
+            raise_on_click: RaiseMode::default(),
             insert_window_strategy: InsertWindowStrategy::default(),
             cursor: CursorConfig::default(),
+            workspace_names: vec![],
+            always_float: vec![],
             layouts: vec![WorkspaceLayout::Tile {
                 nmaster: 1,
                 master_width_factor: 0.5,
             }],
+            tiling_modifier: None,
+            background: default_background(),
             outer_gaps: 0,
+            smart_gaps: false,
+            smart_borders: false,
             inner_gaps: 0,
+            screen_edge_size: default_screen_edge_size(),
+            screen_edge_delay: 0,
+            headless_fallback: false,
+            headless_fallback_size: default_headless_fallback_size(),
         }
     }
 }
@@ -160,6 +506,33 @@ pub enum InsertWindowStrategy {
     AfterFocused,
 }
 
+/// When should clicking on a floating window raise it.
+///
+/// Raising a floating window is the same as focusing it, since the focused window is always drawn
+/// on top of the others.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Hash)]
+pub enum RaiseMode {
+    #[default]
+    /// Always raise floating windows when clicking on them.
+    Always,
+    /// Never raise floating windows when clicking on them. You can still raise them by focusing
+    /// them with key actions.
+    Never,
+    /// Only raise floating windows when clicking on them while holding these modifiers.
+    WithModifier(FhtModifiersState),
+}
+
+impl RaiseMode {
+    /// Whether we should raise a floating window clicked while holding these modifiers.
+    pub fn should_raise(&self, modifiers: FhtModifiersState) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::WithModifier(raise_modifiers) => *raise_modifiers == modifiers,
+        }
+    }
+}
+
 fn default_cursor_theme() -> String {
     std::env::var("XCURSOR_THEME")
         .ok()
@@ -193,6 +566,18 @@ pub struct CursorConfig {
     /// application in order for them to acknowledge the change.
     #[serde(default = "default_cursor_size")]
     pub size: u32,
+
+    /// Should we hide the cursor when typing?
+    ///
+    /// The cursor gets shown again when moving the pointer.
+    #[serde(default)]
+    pub hide_while_typing: bool,
+
+    /// Should we hide the cursor when using a touch screen?
+    ///
+    /// The cursor gets shown again when using the pointer.
+    #[serde(default = "default_true")]
+    pub hide_on_touch: bool,
 }
 
 impl Default for CursorConfig {
@@ -200,6 +585,8 @@ impl Default for CursorConfig {
         Self {
             name: default_cursor_theme(),
             size: default_cursor_size(),
+            hide_while_typing: false,
+            hide_on_touch: true,
         }
     }
 }
@@ -277,3 +664,24 @@ impl Default for RenderConfig {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_always_float() {
+        let general: GeneralConfig =
+            ron::from_str(r#"(always_float: ["pavucontrol", "^org\\.gnome\\..*Dialog$"])"#)
+                .unwrap();
+        let always_float = |app_id: &str| general.always_float.iter().any(|r| r.is_match(app_id));
+        assert!(always_float("pavucontrol"));
+        assert!(always_float("org.gnome.FileDialog"));
+        assert!(!always_float("org.gnome.Nautilus"));
+    }
+
+    #[test]
+    fn rejects_invalid_always_float_regexes() {
+        assert!(ron::from_str::<GeneralConfig>(r#"(always_float: ["(unclosed"])"#).is_err());
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use super::ColorConfig;
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct OutputConfig {
+    /// Whether this output should be the primary one.
+    ///
+    /// The primary output is where windows from removed outputs get migrated to, and what's
+    /// used as a fallback when no output is focused. If no output is marked as primary, the
+    /// first connected output is used.
+    #[serde(default)]
+    pub primary: Option<bool>,
+
+    /// The background color of this output, overriding `general.background`.
+    #[serde(default)]
+    pub background: Option<ColorConfig>,
+
+    /// Force or disable 10-bit color formats for this output, overriding
+    /// `renderer.disable_10bit`.
+    ///
+    /// NOTE: This is only effective on the udev backend, and only when the output gets connected.
+    #[serde(default)]
+    pub force_10bit: Option<bool>,
+
+    /// A friendly name for this output, that you can use wherever an output name is accepted.
+    ///
+    /// This can be overriden at runtime with the `SetOutputAlias` IPC method.
+    #[serde(default)]
+    pub alias: Option<String>,
+
+    /// Whether clients like VR compositors can lease this output connector, using the
+    /// `wp_drm_lease` protocol.
+    ///
+    /// By default, only non-desktop connectors (for example VR headsets) can be leased. Setting
+    /// this to `true` lets a desktop output be leased too: the compositor stops driving it while
+    /// it's leased and brings it back once the lease ends. Setting it to `false` prevents a
+    /// non-desktop connector from being leased.
+    ///
+    /// NOTE: This is only effective on the udev backend, and only when the output gets connected.
+    #[serde(default)]
+    pub allow_lease: Option<bool>,
+}
@@ -1,3 +1,4 @@
+use indexmap::IndexMap;
 use regex::Regex;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
@@ -19,6 +20,26 @@ fn deserialize_regex<'de, D: Deserializer<'de>>(
     })
 }
 
+pub(super) fn serialize_regexes<S: Serializer>(
+    regexes: &[Regex],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(regexes.iter().map(Regex::as_str))
+}
+
+pub(super) fn deserialize_regexes<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Regex>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|regex_string| {
+            Regex::new(regex_string).map_err(|err| {
+                <D::Error as serde::de::Error>::custom(format!("Invalid regex string! {err}"))
+            })
+        })
+        .collect()
+}
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct WindowRulePattern {
     /// The workspace index the window is getting spawned on.
@@ -79,6 +100,11 @@ fn regex_matches(regex_1: Option<&Regex>, regex_2: Option<&Regex>) -> bool {
 }
 
 impl WindowRulePattern {
+    /// Whether this pattern has no condition at all, in which case it never matches.
+    pub fn is_empty(&self) -> bool {
+        self.workspace.is_none() && self.title.is_none() && self.app_id.is_none()
+    }
+
     pub fn matches(&self, title: &str, app_id: &str, workspace: usize) -> bool {
         if self.workspace.as_ref().is_some_and(|ws| workspace == *ws) {
             return true;
@@ -118,10 +144,77 @@ pub struct WindowMapSettings {
     /// Whether to allow this window to draw client-side decorations
     pub allow_csd: Option<bool>,
 
+    /// Whether this window should be floating when it gets mapped.
+    ///
+    /// This overrides `general.always_float` for this window.
+    #[serde(default)]
+    pub floating: Option<bool>,
+
+    /// Whether this window should be centered in its workspace when it gets mapped floating.
+    ///
+    /// This overrides `general.center_new_floating` for this window.
+    #[serde(default)]
+    pub centered: Option<bool>,
+
     /// On which specific workspace of the output should we map the window?
     ///
     /// NOTE: This is the workspace *index*
     pub workspace: Option<usize>,
+
+    /// On which named workspace of the output should we map the window?
+    ///
+    /// This takes precedence over `workspace`. If no workspace has this name, the window gets
+    /// mapped on the active workspace.
+    #[serde(default)]
+    pub workspace_name: Option<String>,
+
+    /// Whether to focus this window when it gets mapped.
+    ///
+    /// This overrides `general.focus_new_windows` for this window.
+    ///
+    /// NOTE: Rules are matched when the window is first configured, but focus settings are
+    /// evaluated when the window is mapped, against the window that is focused at that time.
+    #[serde(default)]
+    pub focus: Option<bool>,
+
+    /// Whether to disable pointer acceleration while this window is focused.
+    ///
+    /// Useful for games that want raw pointer input. The device settings are restored as soon as
+    /// the window loses focus or gets closed.
+    #[serde(default)]
+    pub disable_pointer_accel: Option<bool>,
+
+    /// A factor applied to scroll amounts while this window is focused.
+    #[serde(default)]
+    pub scroll_factor: Option<f64>,
+}
+
+impl WindowMapSettings {
+    /// Get the input overrides of windows matching these settings.
+    pub fn input_overrides(&self) -> WindowInputOverrides {
+        WindowInputOverrides {
+            disable_pointer_accel: self.disable_pointer_accel.unwrap_or(false),
+            scroll_factor: self.scroll_factor.unwrap_or(1.0),
+        }
+    }
+}
+
+/// Input configuration overrides of a window, applied while it's focused.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WindowInputOverrides {
+    /// Whether pointer acceleration is disabled.
+    pub disable_pointer_accel: bool,
+    /// The factor applied to scroll amounts.
+    pub scroll_factor: f64,
+}
+
+impl Default for WindowInputOverrides {
+    fn default() -> Self {
+        Self {
+            disable_pointer_accel: false,
+            scroll_factor: 1.0,
+        }
+    }
 }
 
 impl Default for WindowMapSettings {
@@ -130,7 +223,52 @@ impl Default for WindowMapSettings {
             output: None,
             border: None,
             allow_csd: None,
+            floating: None,
+            centered: None,
             workspace: None,
+            workspace_name: None,
+            focus: None,
+            disable_pointer_accel: None,
+            scroll_factor: None,
         }
     }
 }
+
+/// Default sizes for windows when they get floated, by app_id regex.
+///
+/// In the configuration, this is a map of app_id regexes to `(width, height)` sizes, and the first
+/// matching regex wins. This avoids having to resize the same windows by hand each time you float
+/// them.
+#[derive(Debug, Clone, Default)]
+pub struct FloatingDefaults(Vec<(Regex, (i32, i32))>);
+
+impl Serialize for FloatingDefaults {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(regex, size)| (regex.as_str(), size)))
+    }
+}
+
+impl<'de> Deserialize<'de> for FloatingDefaults {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let map = IndexMap::<String, (i32, i32)>::deserialize(deserializer)?;
+        map.into_iter()
+            .map(|(regex, size)| {
+                let regex = Regex::new(&regex).map_err(|err| {
+                    <D::Error as serde::de::Error>::custom(format!("Invalid regex string! {err}"))
+                })?;
+                Ok((regex, size))
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl FloatingDefaults {
+    /// Get the default floating size of windows with this app_id, if any.
+    pub fn size_for(&self, app_id: &str) -> Option<(i32, i32)> {
+        self.0
+            .iter()
+            .find(|(regex, _)| regex.is_match(app_id))
+            .map(|(_, size)| *size)
+    }
+}
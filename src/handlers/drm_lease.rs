@@ -4,6 +4,7 @@ use smithay::wayland::drm_lease::{
     DrmLease, DrmLeaseBuilder, DrmLeaseHandler, DrmLeaseRequest, DrmLeaseState, LeaseRejected,
 };
 
+use crate::config::CONFIG;
 use crate::state::State;
 
 impl DrmLeaseHandler for State {
@@ -23,50 +24,98 @@ impl DrmLeaseHandler for State {
         node: DrmNode,
         request: DrmLeaseRequest,
     ) -> Result<DrmLeaseBuilder, LeaseRejected> {
+        // A previous request could have failed after we stopped driving its connectors.
+        self.backend.udev().resume_leased_connectors(
+            node,
+            |lease_id| lease_id.is_none(),
+            &mut self.fht,
+        );
+
         let backend = self
             .backend
             .udev()
             .devices
-            .get(&node)
+            .get_mut(&node)
             .ok_or(LeaseRejected::default())?;
 
-        let mut builder = DrmLeaseBuilder::new(&backend.drm);
+        let mut connectors = vec![];
         for conn in request.connectors {
             if let Some((_, crtc)) = backend
                 .non_desktop_connectors
                 .iter()
                 .find(|(handle, _)| *handle == conn)
             {
-                builder.add_connector(conn);
-                builder.add_crtc(*crtc);
-                let planes = backend
-                    .drm
-                    .planes(crtc)
-                    .map_err(LeaseRejected::with_cause)?;
-                builder.add_plane(planes.primary.handle);
-                if let Some(cursor) = planes.cursor {
-                    builder.add_plane(cursor.handle);
-                }
+                connectors.push((conn, *crtc));
+            } else if let Some((_, crtc)) = backend
+                .desktop_lease_connectors
+                .iter()
+                .find(|(info, _)| info.handle() == conn)
+            {
+                connectors.push((conn, *crtc));
             } else {
                 warn!(
                     ?conn,
-                    "Lease requested for desktop connector, denying request"
+                    "Lease requested for non-leasable connector, denying request"
                 );
                 return Err(LeaseRejected::default());
             }
         }
 
+        // Leasing every output would leave us with nothing to display on.
+        let leased_outputs = connectors
+            .iter()
+            .filter(|(_, crtc)| backend.has_surface(*crtc))
+            .count();
+        if leased_outputs > 0
+            && leased_outputs >= self.fht.outputs().count()
+            && !CONFIG.general.headless_fallback
+        {
+            warn!("Lease requested for every output, denying request");
+            return Err(LeaseRejected::default());
+        }
+
+        let mut builder = DrmLeaseBuilder::new(&backend.drm);
+        for (conn, crtc) in &connectors {
+            builder.add_connector(*conn);
+            builder.add_crtc(*crtc);
+            let planes = backend
+                .drm
+                .planes(crtc)
+                .map_err(LeaseRejected::with_cause)?;
+            builder.add_plane(planes.primary.handle);
+            if let Some(cursor) = planes.cursor {
+                builder.add_plane(cursor.handle);
+            }
+        }
+
+        // Stop driving desktop connectors while they are leased, they get resumed when the lease
+        // ends, or when the next request comes in if this one fails. This is a no-op for
+        // non-desktop connectors.
+        for (conn, crtc) in connectors {
+            backend.remove_surface_for_lease(crtc, &mut self.fht);
+            backend.pending_lease_connectors.push(conn);
+        }
+
         Ok(builder)
     }
 
     fn new_active_lease(&mut self, node: DrmNode, lease: DrmLease) {
         let backend = self.backend.udev().devices.get_mut(&node).unwrap();
+        let lease_id = lease.id();
+        backend.leased_connectors.extend(
+            backend
+                .pending_lease_connectors
+                .drain(..)
+                .map(|conn| (conn, lease_id)),
+        );
         backend.active_leases.push(lease);
     }
 
     fn lease_destroyed(&mut self, node: DrmNode, lease_id: u32) {
-        let backend = self.backend.udev().devices.get_mut(&node).unwrap();
+        let udev = self.backend.udev();
+        let backend = udev.devices.get_mut(&node).unwrap();
         backend.active_leases.retain(|l| l.id() != lease_id);
+        udev.resume_leased_connectors(node, |id| id == Some(lease_id), &mut self.fht);
     }
 }
 
@@ -28,7 +28,7 @@ impl WlrLayerShellHandler for State {
         let output = output
             .as_ref()
             .and_then(Output::from_resource)
-            .unwrap_or_else(|| self.fht.workspaces.keys().next().unwrap().clone());
+            .unwrap_or_else(|| self.fht.primary_output().unwrap().clone());
         let layer_surface = LayerSurface::new(surface, namespace);
         let mut map = layer_map_for_output(&output);
         map.map_layer(&layer_surface)
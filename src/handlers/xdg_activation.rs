@@ -3,7 +3,8 @@ use smithay::input::Seat;
 use smithay::reexports::wayland_server::protocol::wl_surface;
 use smithay::wayland::xdg_activation::{self, XdgActivationHandler};
 
-use crate::state::State;
+use crate::shell::KeyboardFocusTarget;
+use crate::state::{OutputState, State};
 
 /// NOTE: This is really just an arbitrary value that I copied from Anvil's code
 /// Optimally this should be checked based on the client but eh.
@@ -36,11 +37,30 @@ impl XdgActivationHandler for State {
         &mut self,
         _token: xdg_activation::XdgActivationToken,
         token_data: xdg_activation::XdgActivationTokenData,
-        _surface: wl_surface::WlSurface,
+        surface: wl_surface::WlSurface,
     ) {
         if token_data.timestamp.elapsed() < ACTIVATION_TIMEOUT {
             // TODO: Activate the window lmao
         }
+
+        // Until we activate windows, at least let the user know about the window asking for it.
+        let Some((window, output)) = self
+            .fht
+            .find_window_and_output(&surface)
+            .map(|(window, output)| (window.clone(), output.clone()))
+        else {
+            return;
+        };
+        if matches!(
+            &self.fht.focus_state.focus_target,
+            Some(KeyboardFocusTarget::Window(focused)) if *focused == window
+        ) {
+            return;
+        }
+        if let Some(workspace) = self.fht.ws_mut_for(&window) {
+            workspace.set_element_urgent(&window, true);
+            OutputState::get(&output).render_state.queue();
+        }
     }
 }
 
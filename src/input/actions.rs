@@ -1,14 +1,18 @@
+use std::path::Path;
+
+use indexmap::IndexMap;
 use serde::ser::SerializeSeq;
-use serde::{Deserialize, Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use smithay::backend::input::MouseButton;
 use smithay::input::keyboard::{Keysym, ModifiersState};
-use smithay::utils::Serial;
+use smithay::utils::{Point, Serial, Size};
 
 use crate::config::CONFIG;
 use crate::shell::workspaces::tile::WorkspaceElement;
+use crate::shell::workspaces::{Axis, Direction, Edge, SnapRegion};
 use crate::shell::{KeyboardFocusTarget, PointerFocusTarget};
-use crate::state::State;
-use crate::utils::geometry::{PointExt, RectCenterExt};
+use crate::state::{DebugFlag, OutputState, State};
+use crate::utils::geometry::{Local, PointExt, RectCenterExt};
 use crate::utils::output::OutputExt;
 
 /// A list of modifiers you can use in a key pattern.
@@ -103,38 +107,232 @@ pub enum KeyAction {
     /// Change the cfact of the focused window.
     ChangeCfact(f32),
 
+    /// Grow the focused tiled window toward an edge by some amount of pixels, shrinking it if
+    /// the amount is negative.
+    ///
+    /// This adjusts the master width factor or the window cfact depending on the edge. With the
+    /// Tile layout, master windows support the Right edge and stack windows the Left edge, with
+    /// BottomStack it's the Bottom and Top edges, and with CenteredMaster master windows support
+    /// Left and Right while stack windows support the edge facing the master. The edges along
+    /// the stack (Top and Bottom, or Left and Right with BottomStack) change the window cfact.
+    ResizeFocusedTile { edge: Edge, amount: i32 },
+
+    /// Save the layout parameters and window proportions of the current workspace in a preset
+    /// with the given name.
+    ///
+    /// NOTE: Presets are not persistent, they only live as long as the compositor runs.
+    SaveLayoutPreset(String),
+
+    /// Load the layout preset with the given name on the current workspace.
+    LoadLayoutPreset(String),
+
+    /// Save the app_id, title, workspace and geometry of every window to the file at this path.
+    SaveSession(String),
+
+    /// Restore the windows saved with [`KeyAction::SaveSession`] in the file at this path.
+    ///
+    /// This is best-effort: windows mapped during the next minute with the same app_id and title
+    /// as a saved window get its workspace, floating state and geometry.
+    RestoreSession(String),
+
     /// Maximize the focused window on the current workspace.
     ///
     /// NOTE: You cant' have 2 maximized windows at a time.
     MaximizeFocusedWindow,
 
+    /// Toggle the floating state of the focused window on the current workspace.
+    ///
+    /// When tiling the window back, it gets back to the place it had before being floated.
+    ToggleFloatingFocusedWindow,
+
+    /// Toggle the sticky state of the focused window on the current workspace.
+    ///
+    /// Sticky windows are floating, and show up on every workspace of their output by following
+    /// the active workspace.
+    ToggleStickyFocusedWindow,
+
+    /// Toggle the pinned state of the focused window on the current workspace.
+    ///
+    /// Pinned windows are left alone by actions affecting every window of a workspace, like
+    /// `SetWorkspaceFloating` and `ArrangeFloatingWindows`. Unlike sticky windows, they stay on
+    /// their workspace.
+    TogglePinnedFocusedWindow,
+
+    /// Float or tile every window of a workspace on the current output.
+    ///
+    /// If no workspace index is given, use the active workspace.
+    SetWorkspaceFloating {
+        workspace: Option<usize>,
+        floating: bool,
+    },
+
+    /// Snap the focused window to a region of the current workspace.
+    ///
+    /// This works with floating windows, and with any window if the active layout is `Floating`.
+    SnapFocusedWindow(SnapRegion),
+
+    /// Center the focused window in the current workspace, keeping its size.
+    ///
+    /// This works with floating windows, and with any window if the active layout is `Floating`.
+    CenterFocusedWindow,
+
+    /// Toggle maximizing the focused window only horizontally or only vertically.
+    ///
+    /// Toggling again restores the window location and size on that axis. This only works with
+    /// floating windows.
+    ToggleMaximizeFocusedWindowAxis(Axis),
+
+    /// Dock the focused window to an edge of the current workspace, keeping its size.
+    ///
+    /// The margin is the gap in pixels to leave between the window and the edge. This only
+    /// works with floating windows.
+    DockFocusedWindow { edge: Edge, margin: i32 },
+
+    /// Resize the focused window to the largest area of the current workspace that no other
+    /// window covers.
+    ///
+    /// This only works with floating windows.
+    GrowFocusedWindowToEmptySpace,
+
+    /// Arrange the floating windows of the current workspace in a grid, keeping them floating.
+    ArrangeFloatingWindows,
+
+    /// Toggle the scratchpad with this name.
+    ///
+    /// If the scratchpad holds a window, show it floating and centered on the current workspace.
+    /// Otherwise, hide the focused window in it.
+    ToggleScratchpad(String),
+
+    /// Hide a window in the stash, focusing the next window of its workspace.
+    ///
+    /// If `window_id` is not given, the focused window is stashed.
+    ///
+    /// The stash is a stack of hidden windows, distinct from scratchpads. It only lives as long as
+    /// the compositor runs.
+    StashAndFocusNext {
+        #[serde(default)]
+        window_id: Option<u64>,
+    },
+
+    /// Show the most recently stashed window on the current workspace, and focus it.
+    PopStash,
+
+    /// Resize the focused window to a fraction of the current workspace usable area.
+    ///
+    /// The fractions are clamped between 0.05 and 1.0. This only works with floating windows.
+    ResizeFocusedWindowRelative { width_frac: f64, height_frac: f64 },
+
     /// Focus the next available window on the current workspace.
     FocusNextWindow,
 
     /// Focus the previous available window on the current workspace.
     FocusPreviousWindow,
 
+    /// Focus the next floating window on the current workspace, skipping tiled ones.
+    FocusNextFloatingWindow,
+
+    /// Focus the previous floating window on the current workspace, skipping tiled ones.
+    FocusPreviousFloatingWindow,
+
+    /// Focus the next tiled window on the current workspace, skipping floating ones.
+    FocusNextTiledWindow,
+
+    /// Focus the previous tiled window on the current workspace, skipping floating ones.
+    FocusPreviousTiledWindow,
+
+    /// Focus the closest window in this direction from the focused one on the current workspace.
+    FocusWindowInDirection(Direction),
+
     /// Swap the current and next window placements.
     SwapWithNextWindow,
 
     /// Swap the current and previous window placements.
     SwapWithPreviousWindow,
 
+    /// Move the focused window to the master slot, putting the previous master window where it
+    /// was.
+    PromoteFocusedWindowToMaster,
+
+    /// Swap the current window with the closest window in this direction from it.
+    SwapWithWindowInDirection(Direction),
+
     /// Focus the next available output.
     FocusNextOutput,
 
     /// Focus the previous available output.
     FocusPreviousOutput,
 
+    /// Move every window of the output named `from` to the workspace with the same index on the
+    /// output named `to`.
+    ///
+    /// This is what happens when an output gets disconnected, use it before unplugging one. The
+    /// `from` output itself stays enabled, and new windows can still get mapped on it.
+    EvacuateOutput { from: String, to: String },
+
     /// Close the currently focused window
     CloseFocusedWindow,
 
     /// Focus the workspace at a given index on the focused output.
     FocusWorkspace(usize),
 
+    /// Focus the next workspace on the focused output.
+    ///
+    /// This stops at the last workspace, unless `general.workspace_switch_wraps` is enabled.
+    FocusNextWorkspace,
+
+    /// Focus the previous workspace on the focused output.
+    ///
+    /// This stops at the first workspace, unless `general.workspace_switch_wraps` is enabled.
+    FocusPreviousWorkspace,
+
+    /// Focus the workspace `offset` workspaces away from the active one, on the output with this
+    /// name, or the focused output.
+    ///
+    /// This stops at the first and last workspaces, unless `general.workspace_switch_wraps` is
+    /// enabled.
+    FocusWorkspaceRelative { offset: i32, output: Option<String> },
+
+    /// Focus the previously active workspace on the output with this name, or the focused output.
+    ///
+    /// Using this repeatedly toggles back and forth between two workspaces.
+    FocusLastWorkspace { output: Option<String> },
+
+    /// Rename the workspace at a given index on the focused output, or clear its name with `None`.
+    ///
+    /// This overrides the name from `general.workspace_names` until the config gets reloaded.
+    SetWorkspaceName {
+        workspace: usize,
+        name: Option<String>,
+    },
+
     /// Send the focused window to the workspace at a given index on the focused output.
     SendFocusedWindowToWorkspace(usize),
 
+    /// Send the focused window to the workspace `offset` workspaces away from the active one.
+    ///
+    /// This stops at the first and last workspaces, unless `general.workspace_switch_wraps` is
+    /// enabled.
+    SendFocusedWindowToWorkspaceRelative(i32),
+
+    /// Send the focused window to the workspace at a given index on the focused output, then
+    /// switch to that workspace and keep the window focused.
+    SendFocusedWindowToWorkspaceAndFollow(usize),
+
+    /// Enable or disable a renderer debug flag at runtime.
+    ///
+    /// This overrides the value from the configuration until it gets reloaded.
+    SetDebugFlag(DebugFlag, bool),
+
+    /// Freeze or unfreeze input, for example when recording a screencast.
+    ///
+    /// While input is frozen, no pointer, touch or keyboard event reaches clients, and only the
+    /// keybinds bound to `SetInputFrozen(false)` work, so make sure you have one!
+    SetInputFrozen(bool),
+
+    /// Enable or disable focus follows mouse, overriding `general.focus_follows_mouse` until the
+    /// configuration gets reloaded.
+    SetFocusFollowsMouse(bool),
+
     /// Do nothing.
     ///
     /// This is the same as disabling the key pattern for this action.
@@ -160,11 +358,54 @@ pub struct KeyPattern(
     pub Keysym,
 );
 
+/// Key patterns sharing the same modifiers and key action.
+///
+/// The keysym can either be a single keysym name, or a list of them, for example
+/// `([SUPER], ["j", "Down"])`. This gets expanded into one [`KeyPattern`] per keysym.
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Hash)]
+struct KeyPatterns(
+    FhtModifiersState,
+    #[serde(deserialize_with = "ser::deserialize_keysyms")] Vec<Keysym>,
+);
+
+/// Deserialize a keybinds table, expanding entries bound to multiple keysyms.
+pub fn deserialize_keybinds<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<IndexMap<KeyPattern, KeyAction>, D::Error> {
+    let keybinds = IndexMap::<KeyPatterns, KeyAction>::deserialize(deserializer)?;
+    Ok(keybinds
+        .into_iter()
+        .flat_map(|(KeyPatterns(modifiers, keysyms), action)| {
+            keysyms
+                .into_iter()
+                .map(move |keysym| (KeyPattern(modifiers, keysym), action.clone()))
+        })
+        .collect())
+}
+
+/// Get the [`Keysym`] with this name, if any.
+pub fn keysym_from_name(name: &str) -> Option<Keysym> {
+    use smithay::input::keyboard::xkb::{self, keysyms};
+
+    // From the xkb rust crate itself, they recommend searching with `KEY_NO_FLAGS` then search
+    // with `CASE_INSENSITIVE` to be more precise in your search, since
+    // `KEYSYM_CASE_INSENSITIVE` will always return the lowercase letter
+    match xkb::keysym_from_name(name, xkb::KEYSYM_NO_FLAGS).raw() {
+        keysyms::KEY_NoSymbol => {
+            match xkb::keysym_from_name(name, xkb::KEYSYM_CASE_INSENSITIVE).raw() {
+                keysyms::KEY_NoSymbol => None,
+                keysym => Some(keysym.into()),
+            }
+        }
+        keysym => Some(keysym.into()),
+    }
+}
+
 mod ser {
+    use std::fmt;
 
-    use serde::de::Unexpected;
+    use serde::de::{SeqAccess, Unexpected, Visitor};
     use serde::{Deserialize, Deserializer, Serializer};
-    use smithay::input::keyboard::xkb::{self, keysyms};
     use smithay::input::keyboard::Keysym;
 
     pub fn serialize_keysym<S: Serializer>(
@@ -178,22 +419,46 @@ mod ser {
         deserializer: D,
     ) -> Result<Keysym, D::Error> {
         let name = String::deserialize(deserializer)?;
+        keysym_named(&name)
+    }
+
+    fn keysym_named<E: serde::de::Error>(name: &str) -> Result<Keysym, E> {
+        super::keysym_from_name(name)
+            .ok_or_else(|| E::invalid_value(Unexpected::Str(name), &"Invalid keysym!"))
+    }
+
+    pub fn deserialize_keysyms<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Keysym>, D::Error> {
+        struct KeysymsVisitor;
+
+        impl<'de> Visitor<'de> for KeysymsVisitor {
+            type Value = Vec<Keysym>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a keysym name or a list of keysym names")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, name: &str) -> Result<Self::Value, E> {
+                Ok(vec![keysym_named(name)?])
+            }
 
-        // From the xkb rust crate itself, they recommend searching with `KEY_NO_FLAGS` then search
-        // with `CASE_INSENSITIVE` to be more precise in your search, since
-        // `KEYSYM_CASE_INSENSITIVE` will always return the lowercase letter
-        match xkb::keysym_from_name(&name, xkb::KEYSYM_NO_FLAGS).raw() {
-            keysyms::KEY_NoSymbol => {
-                match xkb::keysym_from_name(&name, xkb::KEYSYM_CASE_INSENSITIVE).raw() {
-                    keysyms::KEY_NoSymbol => Err(<D::Error as serde::de::Error>::invalid_value(
-                        Unexpected::Str(&name),
-                        &"Invalid keysym!",
-                    )),
-                    keysym => Ok(keysym.into()),
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut keysyms = Vec::new();
+                while let Some(name) = seq.next_element::<String>()? {
+                    keysyms.push(keysym_named(&name)?);
                 }
+                if keysyms.is_empty() {
+                    return Err(<A::Error as serde::de::Error>::invalid_length(
+                        0,
+                        &"at least one keysym",
+                    ));
+                }
+                Ok(keysyms)
             }
-            keysym => Ok(keysym.into()),
         }
+
+        deserializer.deserialize_any(KeysymsVisitor)
     }
 }
 
@@ -212,8 +477,22 @@ impl State {
                 .fht
                 .stop
                 .store(true, std::sync::atomic::Ordering::SeqCst),
-            KeyAction::ReloadConfig => self.reload_config(),
+            KeyAction::ReloadConfig => {
+                if let Err(err) = self.reload_config() {
+                    warn!(?err, "Failed to reload config!");
+                }
+            }
             KeyAction::RunCommand(cmd) => crate::utils::spawn(cmd),
+            KeyAction::SetDebugFlag(flag, enabled) => {
+                self.fht.debug_overrides.set(flag, enabled);
+                for output in self.fht.outputs() {
+                    OutputState::get(output).render_state.queue();
+                }
+            }
+            KeyAction::SetInputFrozen(frozen) => self.fht.input_frozen = frozen,
+            KeyAction::SetFocusFollowsMouse(enabled) => {
+                self.fht.focus_follows_mouse_override = Some(enabled)
+            }
             KeyAction::SelectNextLayout => active.select_next_layout(),
             KeyAction::SelectPreviousLayout => active.select_previous_layout(),
             KeyAction::ChangeMwfact(delta) => active.change_mwfact(delta),
@@ -228,6 +507,21 @@ impl State {
                     active.arrange_tiles();
                 }
             }
+            KeyAction::ResizeFocusedTile { edge, amount } => {
+                if let Some(window) = active.focused().cloned() {
+                    active.resize_element_directional(&window, edge, amount);
+                }
+            }
+            KeyAction::SaveLayoutPreset(name) => active.save_layout_preset(name),
+            KeyAction::LoadLayoutPreset(name) => active.load_layout_preset(&name),
+            KeyAction::SaveSession(path) => match self.fht.save_session(Path::new(&path)) {
+                Ok(count) => info!(?path, count, "Saved session."),
+                Err(err) => warn!(?err, ?path, "Failed to save session!"),
+            },
+            KeyAction::RestoreSession(path) => match self.fht.restore_session(Path::new(&path)) {
+                Ok(count) => info!(?path, count, "Restoring session."),
+                Err(err) => warn!(?err, ?path, "Failed to restore session!"),
+            },
             KeyAction::MaximizeFocusedWindow => {
                 if let Some(window) = active.focused().cloned() {
                     let new_maximized = !window.maximized();
@@ -235,6 +529,130 @@ impl State {
                     active.arrange_tiles();
                 }
             }
+            KeyAction::ToggleFloatingFocusedWindow => {
+                if let Some(window) = active.focused().cloned() {
+                    active.toggle_element_floating(&window);
+                }
+            }
+            KeyAction::ToggleStickyFocusedWindow => {
+                if let Some(window) = active.focused().cloned() {
+                    active.toggle_element_sticky(&window);
+                }
+            }
+            KeyAction::TogglePinnedFocusedWindow => {
+                if let Some(window) = active.focused().cloned() {
+                    active.toggle_element_pinned(&window);
+                }
+            }
+            KeyAction::SetWorkspaceFloating {
+                workspace,
+                floating,
+            } => match workspace {
+                Some(idx) => {
+                    if let Some(workspace) = wset.workspaces_mut().nth(idx) {
+                        workspace.set_all_floating(floating);
+                    }
+                }
+                None => active.set_all_floating(floating),
+            },
+            KeyAction::SnapFocusedWindow(region) => {
+                if let Some(window) = active.focused().cloned() {
+                    active.snap_element(&window, region);
+                }
+            }
+            KeyAction::CenterFocusedWindow => {
+                if let Some(window) = active.focused().cloned() {
+                    active.snap_element(&window, SnapRegion::Center);
+                }
+            }
+            KeyAction::ToggleMaximizeFocusedWindowAxis(axis) => {
+                if let Some(window) = active.focused().cloned() {
+                    active.toggle_element_axis_maximized(&window, axis);
+                }
+            }
+            KeyAction::DockFocusedWindow { edge, margin } => {
+                if let Some(window) = active.focused().cloned() {
+                    active.dock_element(&window, edge, margin);
+                }
+            }
+            KeyAction::GrowFocusedWindowToEmptySpace => {
+                if let Some(window) = active.focused().cloned() {
+                    active.grow_element_to_empty_space(&window);
+                }
+            }
+            KeyAction::ArrangeFloatingWindows => {
+                active.arrange_floating_tiles();
+            }
+            KeyAction::ToggleScratchpad(name) => {
+                if let Some(tile) = self.fht.scratchpads.remove(&name) {
+                    let window = tile.element.clone();
+                    let active = self.fht.wset_mut_for(output).active_mut();
+                    active.insert_tile(tile);
+                    active.set_element_floating(&window, true);
+                    active.snap_element(&window, SnapRegion::Center);
+                    active.focus_element(&window);
+
+                    if CONFIG.general.cursor_warps {
+                        let center = active.element_geometry(&window).unwrap().center();
+                        self.move_pointer(center.to_f64());
+                    }
+                    self.set_focus_target(Some(window.into()));
+                } else {
+                    let active = self.fht.wset_mut_for(output).active_mut();
+                    let Some(window) = active.focused().cloned() else {
+                        return;
+                    };
+                    let tile = active.remove_tile(&window).unwrap();
+                    let new_focus = active.focused().cloned();
+                    self.fht.scratchpads.insert(name, tile);
+                    self.set_focus_target(new_focus.map(Into::into));
+                }
+            }
+            KeyAction::StashAndFocusNext { window_id } => {
+                let window = match window_id {
+                    Some(window_id) => self
+                        .fht
+                        .all_windows()
+                        .find(|window| window.uid() == window_id)
+                        .cloned(),
+                    None => active.focused().cloned(),
+                };
+                let Some(window) = window else {
+                    return;
+                };
+                let workspace = self.fht.ws_mut_for(&window).unwrap();
+                let tile = workspace.remove_tile(&window).unwrap();
+                let new_focus = workspace.focused().cloned();
+                self.fht.stash.push(tile);
+                // Only move the focus if the stashed window held it.
+                if matches!(current_focus, Some(KeyboardFocusTarget::Window(ref w)) if *w == window)
+                {
+                    self.set_focus_target(new_focus.map(Into::into));
+                }
+            }
+            KeyAction::PopStash => {
+                let Some(tile) = self.fht.stash.pop() else {
+                    return;
+                };
+                let window = tile.element.clone();
+                let active = self.fht.wset_mut_for(output).active_mut();
+                active.insert_tile(tile);
+                active.focus_element(&window);
+
+                if CONFIG.general.cursor_warps {
+                    let center = active.element_geometry(&window).unwrap().center();
+                    self.move_pointer(center.to_f64());
+                }
+                self.set_focus_target(Some(window.into()));
+            }
+            KeyAction::ResizeFocusedWindowRelative {
+                width_frac,
+                height_frac,
+            } => {
+                if let Some(window) = active.focused().cloned() {
+                    active.resize_element_relative(&window, width_frac, height_frac);
+                }
+            }
             KeyAction::FocusNextWindow => {
                 let new_focus = active.focus_next_element().cloned();
                 if let Some(window) = new_focus {
@@ -255,6 +673,38 @@ impl State {
                     self.set_focus_target(Some(window.into()));
                 }
             }
+            KeyAction::FocusNextFloatingWindow | KeyAction::FocusNextTiledWindow => {
+                let floating = matches!(action, KeyAction::FocusNextFloatingWindow);
+                let new_focus = active.focus_next_element_filtered(floating).cloned();
+                if let Some(window) = new_focus {
+                    if CONFIG.general.cursor_warps {
+                        let center = active.element_geometry(&window).unwrap().center();
+                        self.move_pointer(center.to_f64())
+                    }
+                    self.set_focus_target(Some(window.into()));
+                }
+            }
+            KeyAction::FocusPreviousFloatingWindow | KeyAction::FocusPreviousTiledWindow => {
+                let floating = matches!(action, KeyAction::FocusPreviousFloatingWindow);
+                let new_focus = active.focus_previous_element_filtered(floating).cloned();
+                if let Some(window) = new_focus {
+                    if CONFIG.general.cursor_warps {
+                        let center = active.element_geometry(&window).unwrap().center();
+                        self.move_pointer(center.to_f64())
+                    }
+                    self.set_focus_target(Some(window.into()));
+                }
+            }
+            KeyAction::FocusWindowInDirection(direction) => {
+                let new_focus = active.focus_element_in_direction(direction).cloned();
+                if let Some(window) = new_focus {
+                    if CONFIG.general.cursor_warps {
+                        let center = active.element_geometry(&window).unwrap().center();
+                        self.move_pointer(center.to_f64())
+                    }
+                    self.set_focus_target(Some(window.into()));
+                }
+            }
             KeyAction::SwapWithNextWindow => {
                 active.swap_with_next_element();
                 if let Some(window) = active.focused().cloned() {
@@ -265,6 +715,21 @@ impl State {
                     self.set_focus_target(Some(window.into()));
                 }
             }
+            KeyAction::PromoteFocusedWindowToMaster => {
+                if let Some(window) = active.focused().cloned() {
+                    active.promote_element_to_master(&window);
+                }
+            }
+            KeyAction::SwapWithWindowInDirection(direction) => {
+                active.swap_with_element_in_direction(direction);
+                if let Some(window) = active.focused().cloned() {
+                    if CONFIG.general.cursor_warps {
+                        let center = active.element_geometry(&window).unwrap().center();
+                        self.move_pointer(center.to_f64())
+                    }
+                    self.set_focus_target(Some(window.into()));
+                }
+            }
             KeyAction::SwapWithPreviousWindow => {
                 active.swap_with_previous_element();
                 if let Some(window) = active.focused().cloned() {
@@ -299,11 +764,8 @@ impl State {
                     .next()
                     .unwrap()
                     .clone();
-                if CONFIG.general.cursor_warps {
-                    let center = output.geometry().center();
-                    self.move_pointer(center.to_f64());
-                }
-                self.fht.focus_state.output.replace(output).unwrap();
+                self.fht.focus_state.output.replace(output.clone()).unwrap();
+                self.warp_pointer_to_output(&output);
             }
             KeyAction::FocusPreviousOutput => {
                 let outputs_len = self.fht.workspaces.len();
@@ -329,11 +791,30 @@ impl State {
                     .next()
                     .unwrap()
                     .clone();
-                if CONFIG.general.cursor_warps {
-                    let center = output.geometry().center();
-                    self.move_pointer(center.to_f64());
+                self.fht.focus_state.output.replace(output.clone()).unwrap();
+                self.warp_pointer_to_output(&output);
+            }
+            KeyAction::EvacuateOutput { from, to } => {
+                let (Some(from_output), Some(to_output)) =
+                    (self.fht.output_named(&from), self.fht.output_named(&to))
+                else {
+                    warn!(
+                        ?from,
+                        ?to,
+                        "Can't evacuate output, no output with this name!"
+                    );
+                    return;
+                };
+                if from_output == to_output {
+                    return;
+                }
+
+                // Evacuating the focused output moves the focus to the destination.
+                let focused = self.fht.focus_state.output.as_ref() == Some(&from_output);
+                self.fht.evacuate_output(&from_output, &to_output);
+                if focused {
+                    self.warp_pointer_to_output(&to_output);
                 }
-                self.fht.focus_state.output.replace(output).unwrap();
             }
             KeyAction::CloseFocusedWindow => {
                 if let Some(KeyboardFocusTarget::Window(window)) = current_focus {
@@ -346,19 +827,113 @@ impl State {
                     self.set_focus_target(Some(window.into()));
                 };
             }
+            KeyAction::SetWorkspaceName { workspace, name } => {
+                if let Some(workspace) = wset.workspaces_mut().nth(workspace) {
+                    workspace.set_name(name);
+                }
+            }
+            KeyAction::FocusNextWorkspace => {
+                if let Some(window) = wset.focus_next_workspace(true) {
+                    self.set_focus_target(Some(window.into()));
+                };
+            }
+            KeyAction::FocusPreviousWorkspace => {
+                if let Some(window) = wset.focus_previous_workspace(true) {
+                    self.set_focus_target(Some(window.into()));
+                };
+            }
+            KeyAction::FocusWorkspaceRelative {
+                offset,
+                output: output_name,
+            } => {
+                let target_output = match output_name {
+                    Some(name) => match self.fht.output_named(&name) {
+                        Some(target_output) => target_output,
+                        None => return,
+                    },
+                    None => output.clone(),
+                };
+                let new_focus = self
+                    .fht
+                    .wset_mut_for(&target_output)
+                    .focus_workspace_relative(offset, true);
+                // Don't steal focus from the focused output.
+                if target_output == *output
+                    && let Some(window) = new_focus
+                {
+                    self.set_focus_target(Some(window.into()));
+                }
+            }
+            KeyAction::FocusLastWorkspace {
+                output: output_name,
+            } => {
+                let target_output = match output_name {
+                    Some(name) => match self.fht.output_named(&name) {
+                        Some(target_output) => target_output,
+                        None => return,
+                    },
+                    None => output.clone(),
+                };
+                let wset = self.fht.wset_mut_for(&target_output);
+                let new_focus = wset
+                    .get_last_active_idx()
+                    .and_then(|idx| wset.set_active_idx(idx, true));
+                // Don't steal focus from the focused output.
+                if target_output == *output
+                    && let Some(window) = new_focus
+                {
+                    self.set_focus_target(Some(window.into()));
+                }
+            }
             KeyAction::SendFocusedWindowToWorkspace(idx) => {
                 let Some(window) = active.focused().cloned() else {
                     return;
                 };
                 let tile = active.remove_tile(&window).unwrap();
                 let new_focus = active.focused().cloned();
-                let idx = idx.clamp(0, 9);
+                let idx = idx.min(wset.workspaces.len() - 1);
                 wset.workspaces[idx].insert_tile(tile);
 
                 if let Some(window) = new_focus {
                     self.set_focus_target(Some(window.into()));
                 }
             }
+            KeyAction::SendFocusedWindowToWorkspaceRelative(offset) => {
+                let Some(window) = active.focused().cloned() else {
+                    return;
+                };
+                let idx = wset.relative_workspace_idx(offset);
+                if idx == wset.get_active_idx() {
+                    return;
+                }
+                let tile = wset.active_mut().remove_tile(&window).unwrap();
+                let new_focus = wset.active().focused().cloned();
+                wset.workspaces[idx].insert_tile(tile);
+
+                if let Some(window) = new_focus {
+                    self.set_focus_target(Some(window.into()));
+                }
+            }
+            KeyAction::SendFocusedWindowToWorkspaceAndFollow(idx) => {
+                let Some(window) = active.focused().cloned() else {
+                    return;
+                };
+                let tile = active.remove_tile(&window).unwrap();
+                let idx = idx.min(wset.workspaces.len() - 1);
+                let workspace = &mut wset.workspaces[idx];
+                workspace.insert_tile(tile);
+                workspace.focus_element(&window);
+                wset.set_active_idx(idx, true);
+
+                if CONFIG.general.cursor_warps {
+                    let center = wset.workspaces[idx]
+                        .element_geometry(&window)
+                        .unwrap()
+                        .center();
+                    self.move_pointer(center.to_f64());
+                }
+                self.set_focus_target(Some(window.into()));
+            }
             _ => {}
         }
     }
@@ -386,6 +961,19 @@ impl From<MouseButton> for FhtMouseButton {
     }
 }
 
+impl FhtMouseButton {
+    /// Get the evdev button code of this button.
+    pub fn button_code(&self) -> u32 {
+        match self {
+            Self::Left => 0x110,
+            Self::Right => 0x111,
+            Self::Middle => 0x112,
+            Self::Back => 0x113,
+            Self::Forward => 0x114,
+        }
+    }
+}
+
 impl Into<MouseButton> for FhtMouseButton {
     fn into(self) -> MouseButton {
         match self {
@@ -415,6 +1003,66 @@ pub enum MouseAction {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct MousePattern(pub FhtModifiersState, pub FhtMouseButton);
 
+/// A hardware switch or button you can bind key actions to, outside of keybinds.
+///
+/// NOTE: The lid switch only works with the udev backend, if libinput exposes a switch device for
+/// it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum SwitchTrigger {
+    /// The laptop lid got closed.
+    LidClose,
+    /// The laptop lid got opened.
+    LidOpen,
+    /// The power button got pressed.
+    ///
+    /// This overrides any keybind using the `XF86PowerOff` key.
+    PowerButton,
+}
+
+/// A corner or edge of an output you can bind key actions to, to make hot corners.
+///
+/// NOTE: These are checked against each output geometry, so an edge shared with another output
+/// also triggers when moving the pointer across it.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ScreenEdge {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl ScreenEdge {
+    /// Get the screen edge at this location inside an output with the given size, if any.
+    ///
+    /// Corners take precedence over edges.
+    pub fn at(
+        location: Point<f64, Local>,
+        output_size: Size<i32, Local>,
+        zone: f64,
+    ) -> Option<Self> {
+        let left = location.x < zone;
+        let right = location.x >= output_size.w as f64 - zone;
+        let top = location.y < zone;
+        let bottom = location.y >= output_size.h as f64 - zone;
+
+        match (left, right, top, bottom) {
+            (true, _, true, _) => Some(Self::TopLeft),
+            (_, true, true, _) => Some(Self::TopRight),
+            (true, _, _, true) => Some(Self::BottomLeft),
+            (_, true, _, true) => Some(Self::BottomRight),
+            (_, _, true, _) => Some(Self::Top),
+            (_, _, _, true) => Some(Self::Bottom),
+            (true, _, _, _) => Some(Self::Left),
+            (_, true, _, _) => Some(Self::Right),
+            _ => None,
+        }
+    }
+}
+
 impl State {
     #[profiling::function]
     pub fn process_mouse_action(&mut self, action: MouseAction, serial: Serial) {
@@ -1,20 +1,27 @@
 pub mod actions;
 
+use std::time::Duration;
+
 pub use actions::*;
 use smithay::backend::input::{
-    AbsolutePositionEvent, Axis, AxisSource, Device, DeviceCapability, Event, GestureBeginEvent,
-    GestureEndEvent, GesturePinchUpdateEvent, GestureSwipeUpdateEvent, InputBackend, InputEvent,
-    KeyState, KeyboardKeyEvent, PointerAxisEvent, PointerButtonEvent, PointerMotionEvent,
-    ProximityState, TabletToolButtonEvent, TabletToolEvent, TabletToolProximityEvent,
-    TabletToolTipEvent, TabletToolTipState,
+    AbsolutePositionEvent, Axis, AxisSource, ButtonState, Device, DeviceCapability, Event,
+    GestureBeginEvent, GestureEndEvent, GesturePinchUpdateEvent, GestureSwipeUpdateEvent,
+    InputBackend, InputEvent, KeyState, KeyboardKeyEvent, PointerAxisEvent, PointerButtonEvent,
+    PointerMotionEvent, ProximityState, Switch, SwitchState, SwitchToggleEvent,
+    TabletToolButtonEvent, TabletToolEvent, TabletToolProximityEvent, TabletToolTipEvent,
+    TabletToolTipState, TouchEvent,
 };
 #[cfg(feature = "udev_backend")]
 use smithay::backend::session::Session;
-use smithay::desktop::{layer_map_for_output, WindowSurfaceType};
+use smithay::desktop::{layer_map_for_output, Window, WindowSurfaceType};
 use smithay::input::keyboard::{FilterResult, Keysym, ModifiersState};
 use smithay::input::pointer::{self, AxisFrame, ButtonEvent, MotionEvent, RelativeMotionEvent};
+use smithay::input::touch::{DownEvent, MotionEvent as TouchMotionEvent, UpEvent};
+use smithay::output::Output;
+use smithay::reexports::calloop::timer::{TimeoutAction, Timer};
+use smithay::reexports::input;
 use smithay::reexports::wayland_server::protocol::wl_pointer;
-use smithay::utils::{Point, SERIAL_COUNTER};
+use smithay::utils::{Logical, Point, Rectangle, Transform, SERIAL_COUNTER};
 use smithay::wayland::compositor::with_states;
 use smithay::wayland::input_method::InputMethodSeat;
 use smithay::wayland::keyboard_shortcuts_inhibit::KeyboardShortcutsInhibitorSeat;
@@ -23,13 +30,123 @@ use smithay::wayland::seat::WaylandFocus;
 use smithay::wayland::shell::wlr_layer::{KeyboardInteractivity, Layer, LayerSurfaceCachedState};
 use smithay::wayland::tablet_manager::{TabletDescriptor, TabletSeatTrait};
 
-use crate::config::CONFIG;
+use crate::config::{WindowInputOverrides, CONFIG};
 use crate::shell::{KeyboardFocusTarget, PointerFocusTarget};
 use crate::state::{OutputState, State};
-use crate::utils::geometry::{Global, PointExt, PointGlobalExt, PointLocalExt, RectGlobalExt};
+use crate::utils::geometry::{
+    Global, PointExt, PointGlobalExt, PointLocalExt, RectCenterExt, RectGlobalExt, SizeExt,
+};
 use crate::utils::output::OutputExt;
 
+/// Map the position of this absolute event to a global position on this output.
+///
+/// Absolute devices like touchscreens and tablets report positions in the orientation of the
+/// panel, so they have to go through the output transform to land in the right place on rotated
+/// outputs.
+fn absolute_position_on_output<B: InputBackend>(
+    event: &impl AbsolutePositionEvent<B>,
+    output: &Output,
+) -> Point<f64, Global> {
+    let output_geo = output.geometry().as_logical();
+    let transform = output.current_transform();
+    // The output geometry is already transformed, get back the size in the panel orientation.
+    let size = transform.invert().transform_size(output_geo.size);
+    map_panel_position(event.position_transformed(size), output_geo, transform)
+}
+
+/// Map a position in the panel orientation of an output to a global position on it.
+///
+/// `output_geo` is the (already transformed) geometry of the output, and `transform` its
+/// transform.
+fn map_panel_position(
+    position: Point<f64, Logical>,
+    output_geo: Rectangle<i32, Logical>,
+    transform: Transform,
+) -> Point<f64, Global> {
+    let size = transform.invert().transform_size(output_geo.size);
+    let position = transform.transform_point_in(position, &size.to_f64());
+    (position + output_geo.loc.to_f64()).as_global()
+}
+
+/// Disable the pointer acceleration of this device, for [`WindowInputOverrides`].
+pub fn disable_pointer_accel(device: &mut input::Device) {
+    let _ = device.config_accel_set_profile(input::AccelProfile::Flat);
+    let _ = device.config_accel_set_speed(0.0);
+}
+
 impl State {
+    /// Get the location of this touch event, mapped to the output configured for its device.
+    fn touch_location<B: InputBackend>(
+        &self,
+        event: &impl AbsolutePositionEvent<B>,
+    ) -> Point<f64, Global> {
+        let device = event.device();
+        // The sysname is the last component of the syspath, like `event5`.
+        let sysname = device
+            .syspath()
+            .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+        let touch_config = CONFIG.input.touch_config(&device.name(), &sysname);
+        let output = touch_config
+            .map_to_output
+            .as_deref()
+            .and_then(|name| self.fht.output_named(name))
+            .unwrap_or_else(|| self.fht.active_output());
+
+        absolute_position_on_output(event, &output)
+    }
+
+    /// Update the screen edge the pointer is in, running its bound action when entering it.
+    ///
+    /// See `screen_edges` in the configuration.
+    fn update_screen_edge(&mut self, pointer_location: Point<f64, Global>, output: &Output) {
+        if CONFIG.screen_edges.is_empty() {
+            return;
+        }
+
+        let zone = CONFIG.general.screen_edge_size as f64;
+        let output_size = output.geometry().size.as_local();
+        let edge = ScreenEdge::at(pointer_location.to_local(output), output_size, zone);
+        if self.fht.screen_edge.as_ref().map(|(edge, _)| *edge) == edge {
+            // Only run the action once when entering the edge.
+            return;
+        }
+
+        if let Some((_, Some(token))) = self.fht.screen_edge.take() {
+            self.fht.loop_handle.remove(token);
+        }
+        let Some(edge) = edge else {
+            return;
+        };
+        let Some(action) = CONFIG.screen_edges.get(&edge).cloned() else {
+            self.fht.screen_edge = Some((edge, None));
+            return;
+        };
+
+        let delay = CONFIG.general.screen_edge_delay;
+        if delay == 0 {
+            self.fht.screen_edge = Some((edge, None));
+            self.process_key_action(action);
+            return;
+        }
+
+        let timer = Timer::from_duration(Duration::from_millis(delay));
+        let token = self
+            .fht
+            .loop_handle
+            .insert_source(timer, move |_, _, state| {
+                if let Some((current, token)) = state.fht.screen_edge.as_mut()
+                    && *current == edge
+                {
+                    *token = None;
+                    state.process_key_action(action.clone());
+                }
+                TimeoutAction::Drop
+            })
+            .unwrap();
+        self.fht.screen_edge = Some((edge, Some(token)));
+    }
+
     /// Update the current keyboard focus with whatever [`KeyboardFocusTarget`] is under the
     /// pointer.
     #[profiling::function]
@@ -54,6 +171,10 @@ impl State {
         };
 
         let pointer_loc = pointer.current_location().as_global();
+        let should_raise = CONFIG
+            .general
+            .raise_on_click
+            .should_raise(self.fht.keyboard.modifier_state().into());
         let layer_map = layer_map_for_output(output);
         let wset = self.fht.wset_mut_for(output);
 
@@ -103,6 +224,13 @@ impl State {
             .map(|(w, _)| w.clone())
         {
             let active = wset.active_mut();
+            let floating = active
+                .tiles
+                .iter()
+                .any(|tile| tile.element == window && tile.floating);
+            if floating && !should_raise {
+                return;
+            }
             active.focus_element(&window);
             self.set_focus_target(Some(window.clone().into()));
         } else if let Some(layer) = layer_map
@@ -125,6 +253,52 @@ impl State {
         }
     }
 
+    /// Focus the window under the pointer, if focus follows mouse is enabled.
+    ///
+    /// Unlike [`State::update_keyboard_focus`], this only focuses windows, layer shells on the top
+    /// and overlay layers block it.
+    fn focus_follows_mouse(&mut self, pointer_loc: Point<f64, Global>, output: &Output) {
+        let enabled = self
+            .fht
+            .focus_follows_mouse_override
+            .unwrap_or(CONFIG.general.focus_follows_mouse);
+        if !enabled
+            || self.fht.pointer.is_grabbed()
+            || self.fht.keyboard.is_grabbed()
+            || self.fht.seat.input_method().keyboard_grabbed()
+        {
+            return;
+        }
+
+        let layer_map = layer_map_for_output(output);
+        if layer_map
+            .layer_under(Layer::Overlay, pointer_loc.as_logical())
+            .or_else(|| layer_map.layer_under(Layer::Top, pointer_loc.as_logical()))
+            .is_some()
+        {
+            return;
+        }
+        drop(layer_map);
+
+        let wset = self.fht.wset_mut_for(output);
+        let Some(window) = wset
+            .active()
+            .element_under(pointer_loc)
+            .map(|(w, _)| w.clone())
+        else {
+            return;
+        };
+        if matches!(
+            &self.fht.focus_state.focus_target,
+            Some(KeyboardFocusTarget::Window(focused)) if *focused == window
+        ) {
+            return;
+        }
+
+        wset.active_mut().focus_element(&window);
+        self.set_focus_target(Some(window.into()));
+    }
+
     /// Update our focus target.
     ///
     /// This will handle additional state changes regarding the old focus and the new focus
@@ -137,13 +311,182 @@ impl State {
 
         if let Some(KeyboardFocusTarget::Window(w)) = ft.as_ref() {
             w.set_activated(true);
+            if let Some(workspace) = self.fht.ws_mut_for(w) {
+                workspace.set_element_urgent(w, false);
+            }
         };
 
         self.fht.focus_state.focus_target = ft.clone();
         self.fht
             .keyboard
             .clone()
-            .set_focus(self, ft, SERIAL_COUNTER.next_serial());
+            .set_focus(self, ft.clone(), SERIAL_COUNTER.next_serial());
+        self.update_input_overrides();
+
+        if CONFIG.general.mouse_follows_focus
+            && let Some(KeyboardFocusTarget::Window(window)) = ft
+        {
+            self.warp_pointer_to_window(&window);
+        }
+    }
+
+    /// Send a synthetic key event to the focused client.
+    ///
+    /// The keysym gets resolved to the first key producing it without modifiers, using the active
+    /// layout of the keymap currently used by the seat keyboard. Returns `false` if no key
+    /// produces it.
+    pub fn send_synthetic_key(&mut self, keysym: Keysym, pressed: bool) -> bool {
+        let keyboard = self.fht.keyboard.clone();
+        let keycode = keyboard.with_xkb_state(self, |context| {
+            let xkb = context.xkb().lock().unwrap();
+            let layout = xkb.active_layout();
+            // SAFETY: We only read from the keymap, the xkb state is left untouched.
+            let keymap = unsafe { xkb.keymap() };
+            let mut keycode = None;
+            keymap.key_for_each(|keymap, code| {
+                if keycode.is_none()
+                    && keymap
+                        .key_get_syms_by_level(code, layout.0, 0)
+                        .contains(&keysym)
+                {
+                    keycode = Some(code);
+                }
+            });
+            keycode
+        });
+        let Some(keycode) = keycode else {
+            return false;
+        };
+
+        let key_state = if pressed {
+            KeyState::Pressed
+        } else {
+            KeyState::Released
+        };
+        let time = {
+            let duration: Duration = self.fht.clock.now().into();
+            duration.as_millis() as u32
+        };
+        // The keyboard handle expects evdev keycodes, xkb ones are offset by 8.
+        self.fht.keyboard.clone().input::<(), _>(
+            self,
+            keycode.raw() - 8,
+            key_state,
+            SERIAL_COUNTER.next_serial(),
+            time,
+            |_, _, _| FilterResult::Forward,
+        );
+
+        true
+    }
+
+    /// Send a synthetic button event to the client under the pointer.
+    pub fn send_synthetic_button(&mut self, button: FhtMouseButton, pressed: bool) {
+        let state = if pressed {
+            ButtonState::Pressed
+        } else {
+            ButtonState::Released
+        };
+        let time = {
+            let duration: Duration = self.fht.clock.now().into();
+            duration.as_millis() as u32
+        };
+        let pointer = self.fht.pointer.clone();
+        pointer.button(
+            self,
+            &ButtonEvent {
+                button: button.button_code(),
+                state,
+                serial: SERIAL_COUNTER.next_serial(),
+                time,
+            },
+        );
+        pointer.frame(self);
+    }
+
+    /// Apply the input overrides of the focused window.
+    ///
+    /// This gets called on focus changes, so the overrides get reverted as soon as the window loses
+    /// focus or gets closed (since the focus moves to another target).
+    pub fn update_input_overrides(&mut self) {
+        let overrides = match &self.fht.focus_state.focus_target {
+            Some(KeyboardFocusTarget::Window(window)) => self
+                .fht
+                .workspaces()
+                .find_map(|(_, wset)| wset.ws_for(window))
+                .and_then(|ws| ws.tiles().find(|tile| tile.element() == window))
+                .map(|tile| tile.input_overrides)
+                .unwrap_or_default(),
+            _ => WindowInputOverrides::default(),
+        };
+
+        if overrides.disable_pointer_accel != self.fht.input_overrides.disable_pointer_accel {
+            for device in &mut self.fht.devices {
+                if !device.has_capability(input::DeviceCapability::Pointer) {
+                    continue;
+                }
+
+                if overrides.disable_pointer_accel {
+                    disable_pointer_accel(device);
+                } else {
+                    crate::config::apply_device_config(device);
+                }
+            }
+        }
+
+        self.fht.input_overrides = overrides;
+    }
+
+    /// Warp the pointer to the center of this window, for `general.mouse_follows_focus`.
+    ///
+    /// The pointer stays in place if it's already inside the window, or if the window center is
+    /// covered by a top or overlay layer shell.
+    fn warp_pointer_to_window(&mut self, window: &Window) {
+        let Some(geometry) = self.fht.window_geometry(window) else {
+            return;
+        };
+        let pointer_loc = self.fht.pointer.current_location().as_global();
+        if geometry.to_f64().contains(pointer_loc) {
+            return;
+        }
+
+        let center = geometry.center();
+        let Some(output) = self
+            .fht
+            .outputs()
+            .find(|output| output.geometry().contains(center))
+            .cloned()
+        else {
+            return;
+        };
+        {
+            let layer_map = layer_map_for_output(&output);
+            let loc = center.to_local(&output).as_logical().to_f64();
+            if layer_map.layer_under(Layer::Overlay, loc).is_some()
+                || layer_map.layer_under(Layer::Top, loc).is_some()
+            {
+                return;
+            }
+        }
+
+        self.move_pointer(center.to_f64());
+    }
+
+    /// Warp the pointer to this newly focused output, for `general.warp_cursor_on_output_focus`.
+    ///
+    /// The pointer goes to the center of the focused window of the active workspace, or to the
+    /// center of the output if there's none.
+    pub fn warp_pointer_to_output(&mut self, output: &Output) {
+        if !CONFIG.general.cursor_warps || !CONFIG.general.warp_cursor_on_output_focus {
+            return;
+        }
+
+        let active = self.fht.wset_for(output).active();
+        let center = active
+            .focused()
+            .and_then(|window| active.element_geometry(window))
+            .map_or_else(|| output.geometry().center(), |geometry| geometry.center());
+        self.move_pointer(center.to_f64());
     }
 
     /// Move the pointe to a specific point.
@@ -211,6 +554,19 @@ impl State {
         let egui = self.fht.egui.outputs.get(&output).cloned().unwrap();
         let mut egui = egui.lock().unwrap();
 
+        // Frozen input only lets keyboard events through, to allow the unfreeze keybind.
+        if self.fht.input_frozen
+            && !matches!(
+                event,
+                InputEvent::DeviceAdded { .. }
+                    | InputEvent::DeviceRemoved { .. }
+                    | InputEvent::Keyboard { .. }
+                    | InputEvent::SwitchToggle { .. }
+            )
+        {
+            return;
+        }
+
         match event {
             InputEvent::DeviceAdded { device } => {
                 if device.has_capability(DeviceCapability::TabletTool) {
@@ -240,6 +596,10 @@ impl State {
                 let time = event.time_msec();
                 let keyboard = self.fht.keyboard.clone();
 
+                if key_state == KeyState::Pressed && CONFIG.general.cursor.hide_while_typing {
+                    self.fht.cursor_hidden_while_typing = true;
+                }
+
                 let mut suppressed_keys = self.fht.suppressed_keys.clone();
 
                 // First candidate: Top/Overlay layershells asking for **Exclusive** keyboard
@@ -254,6 +614,7 @@ impl State {
                     });
                     if data.keyboard_interactivity == KeyboardInteractivity::Exclusive
                         && (data.layer == Layer::Top || data.layer == Layer::Overlay)
+                        && !self.fht.input_frozen
                     {
                         let surface = self.fht.outputs().find_map(|o| {
                             let layer_map = layer_map_for_output(o);
@@ -312,6 +673,15 @@ impl State {
                         // sometime
                         let keysym = *handle.raw_syms().first().unwrap();
 
+                        // Window picking blocks every other keyboard interaction.
+                        if state.fht.pending_window_pick.is_some() {
+                            if key_state == KeyState::Pressed && keysym == Keysym::Escape {
+                                state.finish_window_pick();
+                                suppressed_keys.insert(keysym);
+                            }
+                            return FilterResult::Intercept(KeyAction::None);
+                        }
+
                         if egui.input_event_keyboard(
                             keysym.raw(),
                             key_state == KeyState::Pressed,
@@ -350,6 +720,36 @@ impl State {
                             }
                         }
 
+                        if state.fht.input_frozen {
+                            let key_pattern = KeyPattern(modifiers.into(), keysym);
+                            if key_state == KeyState::Pressed
+                                && let Some(action @ KeyAction::SetInputFrozen(false)) =
+                                    CONFIG.keybinds.get(&key_pattern)
+                            {
+                                suppressed_keys.insert(keysym);
+                                return FilterResult::Intercept(action.clone());
+                            }
+
+                            if key_state == KeyState::Pressed {
+                                suppressed_keys.insert(keysym);
+                                return FilterResult::Intercept(KeyAction::None);
+                            }
+                            // Let through the release of keys pressed before freezing, since the
+                            // client got notified about the press.
+                            if suppressed_keys.remove(&keysym) {
+                                return FilterResult::Intercept(KeyAction::None);
+                            }
+                            return FilterResult::Forward;
+                        }
+
+                        if key_state == KeyState::Pressed
+                            && keysym == Keysym::XF86_PowerOff
+                            && let Some(action) = CONFIG.switches.get(&SwitchTrigger::PowerButton)
+                        {
+                            suppressed_keys.insert(keysym);
+                            return FilterResult::Intercept(action.clone());
+                        }
+
                         if key_state == KeyState::Pressed && !inhibited {
                             let key_pattern = KeyPattern(modifiers.into(), keysym);
                             let action = CONFIG.keybinds.get(&key_pattern).cloned();
@@ -370,12 +770,16 @@ impl State {
                 );
 
                 self.fht.suppressed_keys = suppressed_keys;
+                // Layout switching happens through xkb options, when processing the key.
+                self.update_keyboard_layout();
                 if let Some(action) = action {
                     drop(egui);
                     self.process_key_action(action);
                 }
             }
             InputEvent::PointerMotion { event } => {
+                self.fht.cursor_hidden_while_typing = false;
+                self.fht.cursor_hidden_by_touch = false;
                 let pointer = self.fht.pointer.clone();
                 let mut pointer_location = pointer.current_location().as_global();
                 let under = self.fht.focus_target_under(pointer_location);
@@ -473,6 +877,8 @@ impl State {
                     },
                 );
                 pointer.frame(self);
+                self.update_screen_edge(pointer_location, &output);
+                self.focus_follows_mouse(pointer_location, &output);
 
                 {
                     let location = pointer_location.to_local(&output).to_i32_round();
@@ -500,10 +906,9 @@ impl State {
                 }
             }
             InputEvent::PointerMotionAbsolute { event } => {
-                let output_geo = output.geometry().as_logical();
-                let pointer_location = (event.position_transformed(output_geo.size)
-                    + output_geo.loc.to_f64())
-                .as_global();
+                self.fht.cursor_hidden_while_typing = false;
+                self.fht.cursor_hidden_by_touch = false;
+                let pointer_location = absolute_position_on_output(&event, &output);
                 let serial = SERIAL_COUNTER.next_serial();
 
                 let pointer = self.fht.pointer.clone();
@@ -524,8 +929,12 @@ impl State {
                     },
                 );
                 pointer.frame(self);
+                self.update_screen_edge(pointer_location, &output);
+                self.focus_follows_mouse(pointer_location, &output);
             }
             InputEvent::PointerButton { event } => {
+                self.fht.cursor_hidden_while_typing = false;
+                self.fht.cursor_hidden_by_touch = false;
                 let serial = SERIAL_COUNTER.next_serial();
                 let button = event.button_code();
                 let state = wl_pointer::ButtonState::from(event.state());
@@ -540,13 +949,21 @@ impl State {
                     return;
                 }
 
+                // Window picking blocks every other pointer interaction.
+                if self.fht.pending_window_pick.is_some() {
+                    if state == wl_pointer::ButtonState::Pressed {
+                        self.pick_window_under_pointer();
+                    }
+                    return;
+                }
+
                 if state == wl_pointer::ButtonState::Pressed {
                     self.update_keyboard_focus();
 
                     if let Some(button) = event.button() {
                         let mouse_pattern =
                             MousePattern(self.fht.keyboard.modifier_state().into(), button.into());
-                        if let Some(action) = CONFIG.mousebinds.get(&mouse_pattern).cloned() {
+                        if let Some(action) = CONFIG.mouse_action_for(&mouse_pattern) {
                             self.process_mouse_action(action, serial);
                         }
                     }
@@ -572,6 +989,12 @@ impl State {
                 let vertical_amount = event
                     .amount(Axis::Vertical)
                     .unwrap_or_else(|| vertical_amount_discrete.unwrap_or(0.0) * 3.0 / 120.0);
+                let scroll_factor = self.fht.input_overrides.scroll_factor;
+                let horizontal_amount_discrete =
+                    horizontal_amount_discrete.map(|v| v * scroll_factor);
+                let vertical_amount_discrete = vertical_amount_discrete.map(|v| v * scroll_factor);
+                let horizontal_amount = horizontal_amount * scroll_factor;
+                let vertical_amount = vertical_amount * scroll_factor;
 
                 {
                     let mut frame = AxisFrame::new(event.time_msec()).source(event.source());
@@ -618,19 +1041,11 @@ impl State {
             }
             InputEvent::TabletToolAxis { event } => {
                 let tablet_seat = self.fht.seat.tablet_seat();
-                let Some(output_geometry) = self
-                    .fht
-                    .outputs()
-                    .next()
-                    .map(OutputExt::geometry)
-                    .map(|geo| geo.as_logical())
-                else {
+                let Some(output) = self.fht.outputs().next().cloned() else {
                     return;
                 };
 
-                let pointer_location = (event.position_transformed(output_geometry.size)
-                    + output_geometry.loc.to_f64())
-                .as_global();
+                let pointer_location = absolute_position_on_output(&event, &output);
 
                 let pointer = self.fht.pointer.clone();
                 let under = self.fht.focus_target_under(pointer_location);
@@ -680,22 +1095,14 @@ impl State {
             InputEvent::TabletToolProximity { event } => {
                 let tablet_seat = self.fht.seat.tablet_seat();
 
-                let Some(output_geo) = self
-                    .fht
-                    .outputs()
-                    .next()
-                    .map(OutputExt::geometry)
-                    .map(|geo| geo.as_logical())
-                else {
+                let Some(output) = self.fht.outputs().next().cloned() else {
                     return;
                 };
 
                 let tool = event.tool();
                 tablet_seat.add_tool::<Self>(&self.fht.display_handle, &tool);
 
-                let pointer_location = (event.position_transformed(output_geo.size)
-                    + output_geo.loc.to_f64())
-                .as_global();
+                let pointer_location = absolute_position_on_output(&event, &output);
 
                 let pointer = self.fht.pointer.clone();
                 let under = self.fht.focus_target_under(pointer_location);
@@ -853,6 +1260,79 @@ impl State {
                     },
                 )
             }
+            // NOTE: The egui overlay doesn't handle touch input yet.
+            InputEvent::TouchDown { event } => {
+                if CONFIG.general.cursor.hide_on_touch {
+                    self.fht.cursor_hidden_by_touch = true;
+                }
+                let location = self.touch_location(&event);
+                let under = self.fht.focus_target_under(location);
+
+                if let Some((PointerFocusTarget::Window(window), _)) = under.as_ref() {
+                    if let Some(workspace) = self.fht.ws_mut_for(window) {
+                        workspace.focus_element(window);
+                    }
+                    self.set_focus_target(Some(window.clone().into()));
+                }
+
+                let touch = self.fht.touch.clone();
+                touch.down(
+                    self,
+                    under.map(|(ft, loc)| (ft, loc.as_logical())),
+                    &DownEvent {
+                        slot: event.slot(),
+                        location: location.as_logical(),
+                        serial: SERIAL_COUNTER.next_serial(),
+                        time: event.time_msec(),
+                    },
+                );
+            }
+            InputEvent::TouchMotion { event } => {
+                let location = self.touch_location(&event);
+                let under = self.fht.focus_target_under(location);
+
+                let touch = self.fht.touch.clone();
+                touch.motion(
+                    self,
+                    under.map(|(ft, loc)| (ft, loc.as_logical())),
+                    &TouchMotionEvent {
+                        slot: event.slot(),
+                        location: location.as_logical(),
+                        time: event.time_msec(),
+                    },
+                );
+            }
+            InputEvent::TouchUp { event } => {
+                let touch = self.fht.touch.clone();
+                touch.up(
+                    self,
+                    &UpEvent {
+                        slot: event.slot(),
+                        serial: SERIAL_COUNTER.next_serial(),
+                        time: event.time_msec(),
+                    },
+                );
+            }
+            InputEvent::TouchFrame { .. } => {
+                let touch = self.fht.touch.clone();
+                touch.frame(self);
+            }
+            InputEvent::TouchCancel { .. } => {
+                let touch = self.fht.touch.clone();
+                touch.cancel(self);
+            }
+            InputEvent::SwitchToggle { event } => {
+                let trigger = match (event.switch(), event.state()) {
+                    (Some(Switch::Lid), SwitchState::On) => SwitchTrigger::LidClose,
+                    (Some(Switch::Lid), SwitchState::Off) => SwitchTrigger::LidOpen,
+                    _ => return,
+                };
+                debug!(?trigger, "Switch toggled");
+
+                if let Some(action) = CONFIG.switches.get(&trigger).cloned() {
+                    self.process_key_action(action);
+                }
+            }
             _ => {}
         }
 
@@ -862,3 +1342,35 @@ impl State {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 1920x1080 panel placed at (100, 50), touched at (10, 20) in the panel orientation.
+    fn map(transform: Transform) -> Point<f64, Global> {
+        let size = transform.transform_size((1920, 1080).into());
+        let output_geo = Rectangle::from_loc_and_size((100, 50), size);
+        map_panel_position((10.0, 20.0).into(), output_geo, transform)
+    }
+
+    #[test]
+    fn maps_normal_panel_position() {
+        assert_eq!(map(Transform::Normal), (110.0, 70.0).into());
+    }
+
+    #[test]
+    fn maps_rotated_90_panel_position() {
+        assert_eq!(map(Transform::_90), (1160.0, 60.0).into());
+    }
+
+    #[test]
+    fn maps_rotated_180_panel_position() {
+        assert_eq!(map(Transform::_180), (2010.0, 1110.0).into());
+    }
+
+    #[test]
+    fn maps_rotated_270_panel_position() {
+        assert_eq!(map(Transform::_270), (120.0, 1960.0).into());
+    }
+}
@@ -3,18 +3,32 @@
 mod output;
 mod workspace;
 
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
 pub use output::{Output as IpcOutput, Request as IpcOutputRequest};
+use smithay::desktop::{layer_map_for_output, Window};
 use smithay::reexports::calloop::{self, LoopHandle};
+use smithay::reexports::input::DeviceCapability;
+use smithay::wayland::shell::wlr_layer::Layer;
 pub use workspace::{Request as IpcWorkspaceRequest, Workspace as IpcWorkspace};
 use zbus::{interface, zvariant};
 
-use crate::config::CONFIG;
+use crate::config::{BorderOverrides, ColorConfig, CornerRadius, CONFIG};
+use crate::input::FhtMouseButton;
 use crate::shell::workspaces::tile::WorkspaceElement;
-use crate::state::State;
+use crate::state::{DebugFlag, OutputState, State};
 use crate::utils::dbus::DBUS_CONNECTION;
-use crate::utils::geometry::RectCenterExt;
+use crate::utils::geometry::{PointExt, PointGlobalExt, PointLocalExt, RectLocalExt};
 use crate::utils::output::OutputExt;
 
+/// The version of the IPC API.
+///
+/// This gets bumped on each breaking change to the IPC interfaces, so that clients can check it
+/// before relying on newer methods or properties.
+pub const IPC_API_VERSION: u32 = 1;
+
 pub struct Ipc {
     /// Sender to the compositor state for it process the request.
     to_compositor: calloop::channel::Sender<IpcRequest>,
@@ -26,8 +40,41 @@ pub enum IpcRequest {
     /// Reload the configuration.
     ReloadConfig,
 
-    /// Get a list of all the registered outputs object paths'
-    ListOutputs,
+    /// Reload the configuration, replying with a summary of what changed.
+    ReloadConfigWithSummary,
+
+    /// Quit the compositor.
+    Quit,
+
+    /// Remove the windows whose client died right away, instead of waiting for the next refresh.
+    ReapDeadWindows,
+
+    /// Get the autostart command lines from the configuration.
+    Autostart,
+
+    /// Get the decoration configuration.
+    Decorations,
+
+    /// Spawn the autostart command line at this index again.
+    RunAutostart { index: u32 },
+
+    /// Get a list of all the registered outputs object paths', optionally only the enabled ones.
+    ListOutputs { only_enabled: bool },
+
+    /// Get a summary of the outputs state, without going through their object paths.
+    Monitors,
+
+    /// Get the connectors that can be leased and whether they are leased.
+    LeaseConnectors,
+
+    /// Get the libinput devices managed by the compositor.
+    InputDevices,
+
+    /// Get the protocol IDs of the windows hidden in scratchpads, by scratchpad name.
+    Scratchpads,
+
+    /// Get the active keyboard layout index and the names of the configured layouts.
+    KeyboardLayouts,
 
     /// Get the title of the window with this protocol ID.
     GetWindowTitle { window_id: u64 },
@@ -35,6 +82,12 @@ pub enum IpcRequest {
     /// Get the workspace path holding the window with this protocol ID.
     GetWindowWorkspace { window_id: u64 },
 
+    /// Get the windows of a workspace of the output with this name, or its active workspace.
+    WorkspaceWindows {
+        output: String,
+        index: Option<usize>,
+    },
+
     /// Get the app_id/WM_CLASS of the window with this protocol ID.
     GetWindowAppId { window_id: u64 },
 
@@ -44,28 +97,422 @@ pub enum IpcRequest {
     /// Set the maximized state of the window with this protocol ID.
     SetWindowMaximized { window_id: u64, maximized: bool },
 
+    /// Get the sticky state of the window with this protocol ID.
+    GetWindowSticky { window_id: u64 },
+
+    /// Set the sticky state of the window with this protocol ID.
+    SetWindowSticky { window_id: u64, sticky: bool },
+
+    /// Get the pinned state of the window with this protocol ID.
+    GetWindowPinned { window_id: u64 },
+
+    /// Set the pinned state of the window with this protocol ID.
+    SetWindowPinned { window_id: u64, pinned: bool },
+
+    /// Set the runtime border overrides of the window with this protocol ID.
+    SetWindowBorder {
+        window_id: u64,
+        overrides: BorderOverrides,
+    },
+
     /// Set The active output.
     SetFocusedOutput { name: String },
+
+    /// Set the alias of the output with this name, overriding the configuration.
+    SetOutputAlias { name: String, alias: String },
+
+    /// Set the primary output, overriding the configuration.
+    SetPrimaryOutput { name: String },
+
+    /// Move every window of an output to another one.
+    EvacuateOutput { from: String, to: String },
+
+    /// Focus the previously active workspace of an output.
+    FocusLastWorkspace { output: String },
+
+    /// Send a synthetic key event to the focused client.
+    SendKey { keysym: String, pressed: bool },
+
+    /// Send a synthetic button event to the client under the pointer.
+    SendButton { button: String, pressed: bool },
+
+    /// Enable or disable a renderer debug flag.
+    SetDebugFlag { flag: DebugFlag, enabled: bool },
+
+    /// Enable or disable focus follows mouse.
+    SetFocusFollowsMouse { enabled: bool },
+
+    /// Find the first layer shell with this namespace, optionally only on the output with this
+    /// name.
+    FindLayerShell {
+        namespace: String,
+        output: Option<String>,
+    },
+
+    /// Get the window under the cursor, if any.
+    WindowAtCursor,
+
+    /// Get the layer shell under the cursor, if any.
+    LayerShellAtCursor,
+
+    /// Get the frame timing statistics of the output with this name, or all the outputs.
+    RenderStats { output: Option<String> },
+
+    /// Render a thumbnail of the window with this protocol ID.
+    WindowThumbnail {
+        window_id: u64,
+        max_width: u32,
+        sender: async_std::channel::Sender<Result<(u32, u32, Vec<u8>), IpcError>>,
+    },
+
+    /// Start picking windows, until the user presses Escape.
+    PickWindows {
+        sender: async_std::channel::Sender<Vec<u64>>,
+    },
+}
+
+/// An active window pick, started with the `PickWindows` IPC method.
+///
+/// While a pick is active, the user clicks on windows to pick them, and presses Escape to finish.
+/// Normal pointer and keyboard interaction is blocked.
+pub struct PendingWindowPick {
+    /// The protocol IDs of the picked windows, in pick order.
+    picked: Vec<u64>,
+    /// Sender to give back the picked windows to the IPC when the pick finishes.
+    sender: async_std::channel::Sender<Vec<u64>>,
+}
+
+/// The maximum width of window thumbnails, to avoid huge payloads.
+pub const MAX_THUMBNAIL_WIDTH: u32 = 1024;
+
+/// A window thumbnail requested with the `WindowThumbnail` IPC method.
+///
+/// This gets rendered with the next frame of the output holding the window.
+pub struct PendingThumbnail {
+    /// The window to render.
+    pub window: Window,
+    /// The maximum width of the thumbnail, the window gets downscaled to fit in it.
+    pub max_width: u32,
+    /// Sender to give back the thumbnail width, height and RGBA pixels to the IPC.
+    pub sender: async_std::channel::Sender<Result<(u32, u32, Vec<u8>), IpcError>>,
+}
+
+/// The kind of an [`IpcError`], so that clients can react to it without parsing messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpcErrorCode {
+    /// The requested object (window, output, etc.) does not exist.
+    NotFound,
+    /// An argument of the request is invalid.
+    InvalidArgument,
+    /// The request is not supported.
+    Unsupported,
+    /// Something went wrong inside the compositor.
+    Internal,
+}
+
+/// An error the compositor replies with when it can't process a request.
+#[derive(Debug, Clone)]
+pub struct IpcError {
+    pub code: IpcErrorCode,
+    pub message: String,
+}
+
+impl IpcError {
+    pub fn new(code: IpcErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<IpcError> for zbus::fdo::Error {
+    fn from(err: IpcError) -> Self {
+        // Each code maps to a distinct D-Bus error name.
+        match err.code {
+            IpcErrorCode::NotFound => Self::UnknownObject(err.message),
+            IpcErrorCode::InvalidArgument => Self::InvalidArgs(err.message),
+            IpcErrorCode::Unsupported => Self::NotSupported(err.message),
+            IpcErrorCode::Internal => Self::Failed(err.message),
+        }
+    }
 }
 
 pub enum IpcResponse {
     // Reponses for requests.
-    InvalidProtocolId,
+    Error(IpcError),
+    /// The request succeeded, without any value to give back.
+    Done,
+    /// The number of window rules and reconfigured outputs after reloading the configuration.
+    ConfigReloaded {
+        rules: u32,
+        outputs: u32,
+    },
     WindowPropString(String),
     WindowPropBool(bool),
     Outputs(Vec<String>),
+    /// The number of dead windows that got removed.
+    ReapedWindows(u32),
+    /// The autostart command lines, in order.
+    Autostart(Vec<String>),
+    /// The decoration configuration, serialized in RON.
+    Decorations(String),
+    /// The active workspace index, primary state and focused state of each output, by name.
+    Monitors(HashMap<String, (u8, bool, bool)>),
+    /// Whether each leasable connector is currently leased, by connector name.
+    LeaseConnectors(HashMap<String, bool>),
+    /// The name, dev name, vendor ID, product ID and capabilities of each input device.
+    InputDevices(Vec<(String, String, u32, u32, Vec<String>)>),
+    /// The protocol ID of the window hidden in each scratchpad, by scratchpad name.
+    Scratchpads(HashMap<String, u64>),
+    /// The active keyboard layout index, and the names of the keyboard layouts.
+    KeyboardLayouts(u32, Vec<String>),
+    /// The output name and layer of a layer shell, if it was found.
+    LayerShell(Option<(String, String)>),
+    /// The protocol ID of a window, if it was found.
+    Window(Option<u64>),
+    /// The protocol IDs of windows.
+    Windows(Vec<u64>),
+    /// The namespace, output name and layer of a layer shell, if it was found.
+    LayerShellInfo(Option<(String, String, String)>),
+    /// The output name, recent frame times, average presentation latency (in milliseconds) and
+    /// missed frames count of outputs.
+    RenderStats(Vec<(String, Vec<f64>, f64, u32)>),
 }
 
 #[interface(name = "fht.desktop.Compositor.Ipc")]
 impl Ipc {
+    /// The version of the IPC API, see [`IPC_API_VERSION`]
+    #[zbus(property)]
+    fn api_version(&self) -> u32 {
+        IPC_API_VERSION
+    }
+
+    /// Emitted each time a window gets picked during a `PickWindows` call.
+    #[zbus(signal)]
+    async fn window_picked(ctxt: &zbus::SignalContext<'_>, window_id: u64) -> zbus::Result<()>;
+
+    /// Emitted each time a window geometry changes, with its old and new geometry.
+    ///
+    /// Geometries are given as `(x, y, width, height)`, in the global coordinate space.
+    #[zbus(signal)]
+    async fn window_geometry_changed(
+        ctxt: &zbus::SignalContext<'_>,
+        window_id: u64,
+        old: (i32, i32, i32, i32),
+        new: (i32, i32, i32, i32),
+    ) -> zbus::Result<()>;
+
+    /// Emitted each time the active keyboard layout changes, with its index and name.
+    #[zbus(signal)]
+    async fn keyboard_layout_changed(
+        ctxt: &zbus::SignalContext<'_>,
+        index: u32,
+        name: String,
+    ) -> zbus::Result<()>;
+
+    /// Emitted when reloading the configuration changes the decorations, with the new ones.
+    ///
+    /// See `Decorations` for the format.
+    #[zbus(signal)]
+    async fn decorations_changed(
+        ctxt: &zbus::SignalContext<'_>,
+        decorations: String,
+    ) -> zbus::Result<()>;
+
+    /// Get the active keyboard layout index, and the names of the configured keyboard layouts.
+    ///
+    /// The names are the ones given by xkb, and include the variant, for example
+    /// `English (Dvorak)`. Use the `KeyboardLayoutChanged` signal to track changes.
+    async fn keyboard_layouts(&self) -> zbus::fdo::Result<(u32, Vec<String>)> {
+        if let Err(err) = self.to_compositor.send(IpcRequest::KeyboardLayouts) {
+            warn!(?err, "Failed to send IPC request to the compositor!");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        }
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::KeyboardLayouts(active, layouts)) => Ok((active, layouts)),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Pick multiple windows by clicking on them, until the user presses Escape.
+    ///
+    /// WARNING: This blocks normal pointer and keyboard interaction until the pick is finished.
+    /// Each picked window is also advertised using the `WindowPicked` signal.
+    async fn pick_windows(&self) -> zbus::fdo::Result<Vec<u64>> {
+        let (sender, receiver) = async_std::channel::bounded(1);
+        if let Err(err) = self.to_compositor.send(IpcRequest::PickWindows { sender }) {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        receiver
+            .recv()
+            .await
+            .map_err(|_| zbus::fdo::Error::Failed("A window pick is already running!".to_string()))
+    }
+
+    /// Render a thumbnail of a window, for example to show previews in window switchers.
+    ///
+    /// The window gets downscaled to be at most `max_width` pixels wide, capped to 1024 pixels.
+    /// Returns the thumbnail width, height, and its pixels in RGBA order.
+    async fn window_thumbnail(
+        &self,
+        window_id: u64,
+        max_width: u32,
+    ) -> zbus::fdo::Result<(u32, u32, Vec<u8>)> {
+        let max_width = max_width.clamp(1, MAX_THUMBNAIL_WIDTH);
+        let (sender, receiver) = async_std::channel::bounded(1);
+        if let Err(err) = self.to_compositor.send(IpcRequest::WindowThumbnail {
+            window_id,
+            max_width,
+            sender,
+        }) {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match receiver.recv().await {
+            Ok(Ok(thumbnail)) => Ok(thumbnail),
+            Ok(Err(err)) => Err(err.into()),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
     async fn reload_config(&self) {
         if let Err(err) = self.to_compositor.send(IpcRequest::ReloadConfig) {
             warn!(?err, "Failed to send IPC request to the compositor!");
         }
     }
 
+    /// Reload the configuration, returning the number of window rules and reconfigured outputs.
+    ///
+    /// If the configuration fails to load, the current one is kept and the error is returned.
+    async fn reload_config_with_summary(&self) -> zbus::fdo::Result<(u32, u32)> {
+        if let Err(err) = self.to_compositor.send(IpcRequest::ReloadConfigWithSummary) {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::ConfigReloaded { rules, outputs }) => Ok((rules, outputs)),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Quit the compositor.
+    ///
+    /// This is used by a new compositor instance started with `--replace` to take over.
+    async fn quit(&self) {
+        if let Err(err) = self.to_compositor.send(IpcRequest::Quit) {
+            warn!(?err, "Failed to send IPC request to the compositor");
+        }
+    }
+
+    /// Get the autostart command lines from the configuration, in order.
+    ///
+    /// Use the index of a command line with `run_autostart` to spawn it again.
+    async fn autostart(&self) -> zbus::fdo::Result<Vec<String>> {
+        if let Err(err) = self.to_compositor.send(IpcRequest::Autostart) {
+            warn!(?err, "Failed to send IPC request to the compositor!");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        }
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Autostart(autostart)) => Ok(autostart),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Get the decoration configuration, serialized in RON like in the configuration file.
+    ///
+    /// This includes the border colors, thickness and radius, and whether client-side
+    /// decorations are allowed. Use the `DecorationsChanged` signal to track changes.
+    async fn decorations(&self) -> zbus::fdo::Result<String> {
+        if let Err(err) = self.to_compositor.send(IpcRequest::Decorations) {
+            warn!(?err, "Failed to send IPC request to the compositor!");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        }
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Decorations(decorations)) => Ok(decorations),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Spawn the autostart command line at this index again, see `autostart`.
+    async fn run_autostart(&self, index: u32) -> zbus::fdo::Result<()> {
+        if let Err(err) = self.to_compositor.send(IpcRequest::RunAutostart { index }) {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        }
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Done) => Ok(()),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Remove the windows whose client died right away, returning how many got removed.
+    ///
+    /// Dead windows are removed when the workspaces get refreshed anyway, this is useful for
+    /// scripts recovering from a misbehaving client.
+    async fn reap_dead_windows(&self) -> zbus::fdo::Result<u32> {
+        if let Err(err) = self.to_compositor.send(IpcRequest::ReapDeadWindows) {
+            warn!(?err, "Failed to send IPC request to the compositor!");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        }
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::ReapedWindows(count)) => Ok(count),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
     async fn list_outputs(&self) -> zbus::fdo::Result<Vec<zvariant::ObjectPath>> {
-        if let Err(err) = self.to_compositor.send(IpcRequest::ListOutputs) {
+        self.list_outputs_filtered(false).await
+    }
+
+    /// List the registered outputs object paths, optionally only the enabled ones.
+    ///
+    /// Disconnected outputs stay registered, but disabled, for a moment before getting removed.
+    async fn list_outputs_filtered(
+        &self,
+        only_enabled: bool,
+    ) -> zbus::fdo::Result<Vec<zvariant::ObjectPath>> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::ListOutputs { only_enabled })
+        {
             warn!(?err, "Failed to send IPC request to the compositor!");
             return Err(zbus::fdo::Error::Failed(
                 "Failed to send request to the compositor!".to_string(),
@@ -77,6 +524,89 @@ impl Ipc {
                 .into_iter()
                 .filter_map(|path| zvariant::ObjectPath::try_from(path).ok())
                 .collect()),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Get a summary of all the outputs, by name.
+    ///
+    /// For each output, this returns its active workspace index, whether it's the primary output
+    /// and whether it's the focused output. Use `list_outputs` for the full output objects.
+    async fn monitors(&self) -> zbus::fdo::Result<HashMap<String, (u8, bool, bool)>> {
+        if let Err(err) = self.to_compositor.send(IpcRequest::Monitors) {
+            warn!(?err, "Failed to send IPC request to the compositor!");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        }
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Monitors(monitors)) => Ok(monitors),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Get the connectors that can be leased with the `wp_drm_lease` protocol, by name, and
+    /// whether they are currently leased.
+    ///
+    /// Leased desktop connectors don't show up in `list_outputs` until their lease ends, since the
+    /// compositor doesn't drive them anymore.
+    async fn lease_connectors(&self) -> zbus::fdo::Result<HashMap<String, bool>> {
+        if let Err(err) = self.to_compositor.send(IpcRequest::LeaseConnectors) {
+            warn!(?err, "Failed to send IPC request to the compositor!");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        }
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::LeaseConnectors(connectors)) => Ok(connectors),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Get the input devices managed by the compositor.
+    ///
+    /// For each device, this returns its name, its dev name, vendor and product IDs, and its
+    /// capabilities. Both the name and the dev name can be used as `input.per_device` keys.
+    async fn input_devices(
+        &self,
+    ) -> zbus::fdo::Result<Vec<(String, String, u32, u32, Vec<String>)>> {
+        if let Err(err) = self.to_compositor.send(IpcRequest::InputDevices) {
+            warn!(?err, "Failed to send IPC request to the compositor!");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        }
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::InputDevices(devices)) => Ok(devices),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Get the windows hidden in scratchpads, their protocol ID by scratchpad name.
+    ///
+    /// Use the `ToggleScratchpad` key action to hide and show windows.
+    async fn scratchpads(&self) -> zbus::fdo::Result<HashMap<String, u64>> {
+        if let Err(err) = self.to_compositor.send(IpcRequest::Scratchpads) {
+            warn!(?err, "Failed to send IPC request to the compositor!");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        }
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Scratchpads(scratchpads)) => Ok(scratchpads),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
             Ok(_) => panic!("Something went really wrong..."),
             Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
         }
@@ -95,6 +625,7 @@ impl Ipc {
 
         match self.from_compositor.recv().await {
             Ok(IpcResponse::WindowPropString(title)) => Ok(title),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
             Ok(_) => panic!("Something went really wrong..."),
             Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
         }
@@ -117,6 +648,31 @@ impl Ipc {
         match self.from_compositor.recv().await {
             // SAFETY: The path should be checked beforehand
             Ok(IpcResponse::WindowPropString(path)) => Ok(path.try_into().unwrap()),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Get the protocol IDs of the windows of a single workspace, in the workspace order.
+    ///
+    /// The output is the output name, alias or `active` for the focused output, and the index is
+    /// the workspace index, or a negative value for the active workspace of that output.
+    async fn workspace_windows(&self, output: String, index: i32) -> zbus::fdo::Result<Vec<u64>> {
+        let index = usize::try_from(index).ok();
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::WorkspaceWindows { output, index })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Windows(windows)) => Ok(windows),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
             Ok(_) => panic!("Something went really wrong..."),
             Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
         }
@@ -135,6 +691,7 @@ impl Ipc {
 
         match self.from_compositor.recv().await {
             Ok(IpcResponse::WindowPropString(title)) => Ok(title),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
             Ok(_) => panic!("Something went really wrong..."),
             Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
         }
@@ -153,6 +710,7 @@ impl Ipc {
 
         match self.from_compositor.recv().await {
             Ok(IpcResponse::WindowPropBool(maximized)) => Ok(maximized),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
             Ok(_) => panic!("Something went really wrong..."),
             Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
         }
@@ -172,11 +730,41 @@ impl Ipc {
         }
     }
 
-    async fn set_focused_output(&self, name: String) -> zbus::fdo::Result<()> {
-        if let Err(err) = self
-            .to_compositor
-            .send(IpcRequest::SetFocusedOutput { name })
-        {
+    /// Override the border of the window with this protocol ID, on top of the configuration and
+    /// window rules.
+    ///
+    /// Colors are written like in the configuration, for example `Solid("#6791c9")`. An empty
+    /// color string, or a negative thickness or radius keeps the configured value.
+    ///
+    /// The overrides are cleared when reloading the configuration.
+    async fn set_window_border(
+        &self,
+        window_id: u64,
+        focused_color: String,
+        normal_color: String,
+        thickness: i32,
+        radius: f64,
+    ) -> zbus::fdo::Result<()> {
+        let parse_color = |color: &str| -> zbus::fdo::Result<Option<ColorConfig>> {
+            if color.is_empty() {
+                return Ok(None);
+            }
+            ron::from_str(color).map(Some).map_err(|err| {
+                zbus::fdo::Error::InvalidArgs(format!("Invalid border color {color:?}: {err}"))
+            })
+        };
+
+        let overrides = BorderOverrides {
+            focused_color: parse_color(&focused_color)?,
+            normal_color: parse_color(&normal_color)?,
+            thickness: (thickness >= 0).then(|| thickness.clamp(0, u8::MAX as i32) as u8),
+            radius: (radius >= 0.0).then(|| CornerRadius([radius as f32; 4])),
+        };
+
+        if let Err(err) = self.to_compositor.send(IpcRequest::SetWindowBorder {
+            window_id,
+            overrides,
+        }) {
             warn!(?err, "Failed to send IPC request to the compositor");
             return Err(zbus::fdo::Error::Failed(
                 "Failed to send request to the compositor!".to_string(),
@@ -185,19 +773,393 @@ impl Ipc {
             Ok(())
         }
     }
-}
 
-/// Start the fht-compositor IPC server on the session D-bus.
-///
-/// This will register the ervice `fht.desktop.Compositor` with the interface
-/// `fht.desktop.Compositor.Ipc` to interface with it.
-pub fn start(loop_handle: &LoopHandle<'static, State>) -> zbus::Result<()> {
-    // In order to communicate with the compositor, we need two channels.
-    //
-    // - going from the IPC to the compositor so we can process the request. This is going to be a
-    //   calloop channel since we don't have any access to the [`State`] here.
-    let (to_compositor, from_ipc_channel) = calloop::channel::channel::<IpcRequest>();
-    // - going from the compositor to the IPC, so we can send the response back to the dbus client
+    async fn get_window_sticky(&self, window_id: u64) -> zbus::fdo::Result<bool> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::GetWindowSticky { window_id })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::WindowPropBool(sticky)) => Ok(sticky),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    async fn set_window_sticky(&self, window_id: u64, sticky: bool) -> zbus::fdo::Result<()> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::SetWindowSticky { window_id, sticky })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Get whether the window with this protocol ID is pinned.
+    ///
+    /// Pinned windows are skipped by operations acting on every window of a workspace. This is
+    /// unrelated to sticky windows, that show up on every workspace of their output.
+    async fn get_window_pinned(&self, window_id: u64) -> zbus::fdo::Result<bool> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::GetWindowPinned { window_id })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::WindowPropBool(pinned)) => Ok(pinned),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Pin or unpin the window with this protocol ID.
+    async fn set_window_pinned(&self, window_id: u64, pinned: bool) -> zbus::fdo::Result<()> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::SetWindowPinned { window_id, pinned })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn set_focused_output(&self, name: String) -> zbus::fdo::Result<()> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::SetFocusedOutput { name })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Set a friendly name for an output, that can be used wherever an output name is accepted.
+    ///
+    /// This overrides the `alias` of the outputs configuration, pass an empty alias to go back to
+    /// it.
+    async fn set_output_alias(&self, name: String, alias: String) -> zbus::fdo::Result<()> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::SetOutputAlias { name, alias })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Done) => Ok(()),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Set the primary output by name, overriding the `primary` setting of the outputs
+    /// configuration until it gets reloaded.
+    async fn set_primary_output(&self, name: String) -> zbus::fdo::Result<()> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::SetPrimaryOutput { name })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Done) => Ok(()),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Move every window of the output named `from` to the workspace with the same index on the
+    /// output named `to`.
+    ///
+    /// This is what happens when an output gets disconnected, use it before unplugging one. The
+    /// `from` output itself stays enabled, and new windows can still get mapped on it.
+    async fn evacuate_output(&self, from: String, to: String) -> zbus::fdo::Result<()> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::EvacuateOutput { from, to })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Done) => Ok(()),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Focus the previously active workspace of an output.
+    ///
+    /// The output is the output name, alias or `active` for the focused output.
+    async fn focus_last_workspace(&self, output: String) -> zbus::fdo::Result<()> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::FocusLastWorkspace { output })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Done) => Ok(()),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Send a synthetic key event to the focused client.
+    ///
+    /// The keysym name is resolved like in keybinds, to the first key producing it without
+    /// modifiers. To type a modified keysym, send the modifier key events around it.
+    ///
+    /// WARN: Any IPC client can inject input this way, into whatever client has focus.
+    async fn send_key(&self, keysym: String, pressed: bool) -> zbus::fdo::Result<()> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::SendKey { keysym, pressed })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Done) => Ok(()),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Send a synthetic button event to the client under the pointer.
+    ///
+    /// The button is one of `left`, `middle`, `right`, `back` or `forward`.
+    ///
+    /// WARN: Any IPC client can inject input this way.
+    async fn send_button(&self, button: String, pressed: bool) -> zbus::fdo::Result<()> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::SendButton { button, pressed })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Done) => Ok(()),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Enable or disable a renderer debug flag at runtime.
+    ///
+    /// Available flags are `debug-overlay` and `draw-damage`. These override the values from the
+    /// configuration until it gets reloaded.
+    async fn set_debug_flag(&self, flag: String, enabled: bool) -> zbus::fdo::Result<()> {
+        let flag = flag
+            .parse::<DebugFlag>()
+            .map_err(|err| zbus::fdo::Error::InvalidArgs(err.to_string()))?;
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::SetDebugFlag { flag, enabled })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Enable or disable focus follows mouse at runtime.
+    ///
+    /// This overrides `general.focus_follows_mouse` until the configuration gets reloaded.
+    async fn set_focus_follows_mouse(&self, enabled: bool) -> zbus::fdo::Result<()> {
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::SetFocusFollowsMouse { enabled })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Find the first layer shell with this namespace.
+    ///
+    /// If `output` is not empty, only search the layer shells of the output with this name.
+    ///
+    /// Returns whether a layer shell was found, followed by the name of the output it's on and the
+    /// layer it's in.
+    async fn find_layer_shell(
+        &self,
+        namespace: String,
+        output: String,
+    ) -> zbus::fdo::Result<(bool, String, String)> {
+        let output = (!output.is_empty()).then_some(output);
+        if let Err(err) = self
+            .to_compositor
+            .send(IpcRequest::FindLayerShell { namespace, output })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::LayerShell(Some((output, layer)))) => Ok((true, output, layer)),
+            Ok(IpcResponse::LayerShell(None)) => Ok((false, String::new(), String::new())),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Get the protocol ID of the window under the cursor, or 0 if there's none.
+    ///
+    /// Unlike `PickWindows`, this doesn't wait for any user interaction.
+    async fn window_at_cursor(&self) -> zbus::fdo::Result<u64> {
+        if let Err(err) = self.to_compositor.send(IpcRequest::WindowAtCursor) {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::Window(window_id)) => Ok(window_id.unwrap_or(0)),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Get the layer shell under the cursor.
+    ///
+    /// Returns whether a layer shell was found, followed by its namespace, the name of the output
+    /// it's on and the layer it's in.
+    async fn layer_shell_at_cursor(&self) -> zbus::fdo::Result<(bool, String, String, String)> {
+        if let Err(err) = self.to_compositor.send(IpcRequest::LayerShellAtCursor) {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::LayerShellInfo(Some((namespace, output, layer)))) => {
+                Ok((true, namespace, output, layer))
+            }
+            Ok(IpcResponse::LayerShellInfo(None)) => {
+                Ok((false, String::new(), String::new(), String::new()))
+            }
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+
+    /// Get frame timing statistics, to help diagnosing stutter and presentation issues.
+    ///
+    /// If `output` is not empty, only get the statistics of the output with this name.
+    ///
+    /// Returns for each output its name, the frame times of its recent frames, its average
+    /// presentation latency (all in milliseconds) and how many of its tracked frames missed a
+    /// refresh cycle.
+    async fn render_stats(
+        &self,
+        output: String,
+    ) -> zbus::fdo::Result<Vec<(String, Vec<f64>, f64, u32)>> {
+        let output = (!output.is_empty()).then_some(output);
+        if let Err(err) = self.to_compositor.send(IpcRequest::RenderStats { output }) {
+            warn!(?err, "Failed to send IPC request to the compositor");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        };
+
+        match self.from_compositor.recv().await {
+            Ok(IpcResponse::RenderStats(stats)) => Ok(stats),
+            Ok(IpcResponse::Error(err)) => Err(err.into()),
+            Ok(_) => panic!("Something went really wrong..."),
+            Err(err) => Err(zbus::fdo::Error::Failed(err.to_string())),
+        }
+    }
+}
+
+/// Get the name of a layer shell layer, as advertised to IPC.
+fn layer_name(layer: Layer) -> &'static str {
+    match layer {
+        Layer::Background => "background",
+        Layer::Bottom => "bottom",
+        Layer::Top => "top",
+        Layer::Overlay => "overlay",
+    }
+}
+
+/// Start the fht-compositor IPC server on the session D-bus.
+///
+/// This will register the ervice `fht.desktop.Compositor` with the interface
+/// `fht.desktop.Compositor.Ipc` to interface with it.
+pub fn start(loop_handle: &LoopHandle<'static, State>) -> zbus::Result<()> {
+    // In order to communicate with the compositor, we need two channels.
+    //
+    // - going from the IPC to the compositor so we can process the request. This is going to be a
+    //   calloop channel since we don't have any access to the [`State`] here.
+    let (to_compositor, from_ipc_channel) = calloop::channel::channel::<IpcRequest>();
+    // - going from the compositor to the IPC, so we can send the response back to the dbus client
     //   calling us. Same reason apply here (no access to the [`State`]), + dbus should be
     //   asynchronous, compared to wayland synchronous system.
     let (to_ipc, from_compositor) = async_std::channel::unbounded::<IpcResponse>();
@@ -230,6 +1192,156 @@ pub fn start(loop_handle: &LoopHandle<'static, State>) -> zbus::Result<()> {
 }
 
 impl State {
+    /// Pick the window under the pointer, if there's an active window pick.
+    pub fn pick_window_under_pointer(&mut self) {
+        let pointer_loc = self.fht.pointer.current_location().as_global();
+        let output = self.fht.active_output();
+        let Some(window_id) = self
+            .fht
+            .wset_for(&output)
+            .active()
+            .element_under(pointer_loc)
+            .map(|(window, _)| window.uid())
+        else {
+            return;
+        };
+
+        let Some(pick) = self.fht.pending_window_pick.as_mut() else {
+            return;
+        };
+        if pick.picked.contains(&window_id) {
+            return;
+        }
+        pick.picked.push(window_id);
+
+        async_std::task::block_on(async {
+            let iface_ref = DBUS_CONNECTION
+                .object_server()
+                .interface::<_, Ipc>("/fht/desktop/Compositor")
+                .unwrap();
+            if let Err(err) = Ipc::window_picked(iface_ref.signal_context(), window_id).await {
+                warn!(?err, "Failed to advertise picked window to IPC!");
+            }
+        });
+    }
+
+    /// Compare the window geometries with the last snapshot, advertising the changes to the IPC.
+    ///
+    /// The snapshot is updated in place, only the windows whose geometry changed get touched.
+    pub fn advertise_window_geometry_changes(&mut self) {
+        let mut changes = vec![];
+        let mut window_count = 0;
+        for (output, wset) in &self.fht.workspaces {
+            for workspace in wset.workspaces() {
+                for tile in workspace.tiles() {
+                    window_count += 1;
+                    let geo = tile.geometry().to_global(output);
+                    let new = (geo.loc.x, geo.loc.y, geo.size.w, geo.size.h);
+                    match self.fht.window_geometries.entry(tile.element.uid()) {
+                        Entry::Occupied(mut entry) if *entry.get() != new => {
+                            let old = entry.insert(new);
+                            changes.push((*entry.key(), old, new));
+                        }
+                        Entry::Occupied(_) => (),
+                        // New windows have nothing to compare against.
+                        Entry::Vacant(entry) => {
+                            entry.insert(new);
+                        }
+                    }
+                }
+            }
+        }
+
+        // Forget about closed windows, only walking the windows again when some did close.
+        if self.fht.window_geometries.len() != window_count {
+            let window_ids = self
+                .fht
+                .all_windows()
+                .map(|window| window.uid())
+                .collect::<HashSet<_>>();
+            self.fht
+                .window_geometries
+                .retain(|window_id, _| window_ids.contains(window_id));
+        }
+
+        if changes.is_empty() {
+            return;
+        }
+
+        async_std::task::block_on(async {
+            let iface_ref = DBUS_CONNECTION
+                .object_server()
+                .interface::<_, Ipc>("/fht/desktop/Compositor")
+                .unwrap();
+            for (window_id, old, new) in changes {
+                if let Err(err) =
+                    Ipc::window_geometry_changed(iface_ref.signal_context(), window_id, old, new)
+                        .await
+                {
+                    warn!(?err, "Failed to advertise window geometry change to IPC!");
+                }
+            }
+        });
+    }
+
+    /// Advertise the current decoration configuration to the IPC.
+    pub fn advertise_decorations(&self) {
+        let decorations = match ron::to_string(&CONFIG.decoration) {
+            Ok(decorations) => decorations,
+            Err(err) => {
+                warn!(?err, "Failed to serialize decorations!");
+                return;
+            }
+        };
+
+        async_std::task::block_on(async {
+            let iface_ref = DBUS_CONNECTION
+                .object_server()
+                .interface::<_, Ipc>("/fht/desktop/Compositor")
+                .unwrap();
+            if let Err(err) =
+                Ipc::decorations_changed(iface_ref.signal_context(), decorations).await
+            {
+                warn!(?err, "Failed to advertise decorations change to IPC!");
+            }
+        });
+    }
+
+    /// Check whether the active keyboard layout changed, advertising it to the IPC if so.
+    pub fn update_keyboard_layout(&mut self) {
+        let keyboard = self.fht.keyboard.clone();
+        let (index, name) = keyboard.with_xkb_state(self, |context| {
+            let xkb = context.xkb().lock().unwrap();
+            let layout = xkb.active_layout();
+            (layout.0, xkb.layout_name(layout).to_string())
+        });
+        if self.fht.keyboard_layout_idx == index {
+            return;
+        }
+        self.fht.keyboard_layout_idx = index;
+
+        async_std::task::block_on(async {
+            let iface_ref = DBUS_CONNECTION
+                .object_server()
+                .interface::<_, Ipc>("/fht/desktop/Compositor")
+                .unwrap();
+            if let Err(err) =
+                Ipc::keyboard_layout_changed(iface_ref.signal_context(), index, name).await
+            {
+                warn!(?err, "Failed to advertise keyboard layout change to IPC!");
+            }
+        });
+    }
+
+    /// Finish the active window pick, if any, giving back the picked windows to the IPC.
+    pub fn finish_window_pick(&mut self) {
+        if let Some(PendingWindowPick { picked, sender }) = self.fht.pending_window_pick.take() {
+            if let Err(err) = sender.send_blocking(picked) {
+                warn!(?err, "Failed to send picked windows to IPC!");
+            }
+        }
+    }
+
     /// Process a given IPC request.
     #[profiling::function]
     fn handle_ipc_request(
@@ -238,11 +1350,78 @@ impl State {
         to_ipc: &async_std::channel::Sender<IpcResponse>,
     ) {
         match req {
-            IpcRequest::ReloadConfig => self.reload_config(),
-            IpcRequest::ListOutputs => {
+            IpcRequest::ReloadConfig => {
+                if let Err(err) = self.reload_config() {
+                    warn!(?err, "Failed to reload config!");
+                }
+            }
+            IpcRequest::ReloadConfigWithSummary => {
+                let response = match self.reload_config() {
+                    Ok(summary) => IpcResponse::ConfigReloaded {
+                        rules: summary.rules as u32,
+                        outputs: summary.outputs as u32,
+                    },
+                    Err(err) => IpcResponse::Error(IpcError::new(
+                        IpcErrorCode::InvalidArgument,
+                        format!("Failed to reload config! {err}"),
+                    )),
+                };
+                to_ipc.send_blocking(response).unwrap();
+            }
+            IpcRequest::Quit => self
+                .fht
+                .stop
+                .store(true, std::sync::atomic::Ordering::SeqCst),
+            IpcRequest::Autostart => {
+                to_ipc
+                    .send_blocking(IpcResponse::Autostart(CONFIG.autostart.clone()))
+                    .unwrap();
+            }
+            IpcRequest::Decorations => {
+                let response = match ron::to_string(&CONFIG.decoration) {
+                    Ok(decorations) => IpcResponse::Decorations(decorations),
+                    Err(err) => IpcResponse::Error(IpcError::new(
+                        IpcErrorCode::Internal,
+                        format!("Failed to serialize decorations! {err}"),
+                    )),
+                };
+                to_ipc.send_blocking(response).unwrap();
+            }
+            IpcRequest::RunAutostart { index } => {
+                let Some(cmd) = CONFIG.autostart.get(index as usize) else {
+                    let err = IpcError::new(
+                        IpcErrorCode::NotFound,
+                        format!("No autostart command line at index {index}!"),
+                    );
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                    return;
+                };
+                crate::utils::spawn(cmd.clone());
+                to_ipc.send_blocking(IpcResponse::Done).unwrap();
+            }
+            IpcRequest::ReapDeadWindows => {
+                let mut count = 0;
+                for wset in self.fht.workspaces.values_mut() {
+                    for workspace in wset.workspaces_mut() {
+                        count += workspace.reap_dead_tiles();
+                    }
+                }
+                if count > 0 {
+                    info!(?count, "Reaped dead windows.");
+                    for output in self.fht.outputs() {
+                        OutputState::get(output).render_state.queue();
+                    }
+                }
+
+                to_ipc
+                    .send_blocking(IpcResponse::ReapedWindows(count as u32))
+                    .unwrap();
+            }
+            IpcRequest::ListOutputs { only_enabled } => {
                 let ret = self
                     .fht
                     .outputs()
+                    .filter(|o| !only_enabled || OutputState::get(o).enabled)
                     .map(|o| {
                         format!(
                             "/fht/desktop/Compositor/Output/{}",
@@ -253,6 +1432,86 @@ impl State {
 
                 to_ipc.send_blocking(IpcResponse::Outputs(ret)).unwrap();
             }
+            IpcRequest::Monitors => {
+                let active_output = self.fht.active_output();
+                let primary_output = self.fht.primary_output().cloned();
+                let ret = self
+                    .fht
+                    .outputs()
+                    .map(|o| {
+                        let active_idx = self.fht.wset_for(o).get_active_idx() as u8;
+                        let primary = primary_output.as_ref() == Some(o);
+                        (o.name(), (active_idx, primary, *o == active_output))
+                    })
+                    .collect();
+
+                to_ipc.send_blocking(IpcResponse::Monitors(ret)).unwrap();
+            }
+            IpcRequest::LeaseConnectors => {
+                let connectors = self.backend.lease_connectors();
+                to_ipc
+                    .send_blocking(IpcResponse::LeaseConnectors(connectors))
+                    .unwrap();
+            }
+            IpcRequest::Scratchpads => {
+                let ret = self
+                    .fht
+                    .scratchpads
+                    .iter()
+                    .map(|(name, tile)| (name.clone(), tile.element.uid()))
+                    .collect();
+                to_ipc.send_blocking(IpcResponse::Scratchpads(ret)).unwrap();
+            }
+            IpcRequest::KeyboardLayouts => {
+                let keyboard = self.fht.keyboard.clone();
+                let (active, layouts) = keyboard.with_xkb_state(self, |context| {
+                    let xkb = context.xkb().lock().unwrap();
+                    let layouts = xkb
+                        .layouts()
+                        .map(|layout| xkb.layout_name(layout).to_string())
+                        .collect();
+                    (xkb.active_layout().0, layouts)
+                });
+
+                to_ipc
+                    .send_blocking(IpcResponse::KeyboardLayouts(active, layouts))
+                    .unwrap();
+            }
+            IpcRequest::InputDevices => {
+                const CAPABILITIES: [(DeviceCapability, &str); 7] = [
+                    (DeviceCapability::Keyboard, "keyboard"),
+                    (DeviceCapability::Pointer, "pointer"),
+                    (DeviceCapability::Touch, "touch"),
+                    (DeviceCapability::TabletTool, "tablet-tool"),
+                    (DeviceCapability::TabletPad, "tablet-pad"),
+                    (DeviceCapability::Gesture, "gesture"),
+                    (DeviceCapability::Switch, "switch"),
+                ];
+
+                let ret = self
+                    .fht
+                    .devices
+                    .iter()
+                    .map(|device| {
+                        let capabilities = CAPABILITIES
+                            .iter()
+                            .filter(|(cap, _)| device.has_capability(*cap))
+                            .map(|(_, name)| name.to_string())
+                            .collect();
+                        (
+                            device.name().to_string(),
+                            device.sysname().to_string(),
+                            device.id_vendor(),
+                            device.id_product(),
+                            capabilities,
+                        )
+                    })
+                    .collect();
+
+                to_ipc
+                    .send_blocking(IpcResponse::InputDevices(ret))
+                    .unwrap();
+            }
             IpcRequest::GetWindowTitle { window_id } => {
                 if let Some(window) = self
                     .fht
@@ -263,9 +1522,11 @@ impl State {
                         .send_blocking(IpcResponse::WindowPropString(window.title()))
                         .unwrap();
                 } else {
-                    to_ipc
-                        .send_blocking(IpcResponse::InvalidProtocolId)
-                        .unwrap();
+                    let err = IpcError::new(
+                        IpcErrorCode::NotFound,
+                        format!("No window with protocol ID {window_id}!"),
+                    );
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
                 }
             }
             IpcRequest::GetWindowWorkspace { window_id } => {
@@ -280,11 +1541,35 @@ impl State {
                         .send_blocking(IpcResponse::WindowPropString(ipc_path))
                         .unwrap();
                 } else {
-                    to_ipc
-                        .send_blocking(IpcResponse::InvalidProtocolId)
-                        .unwrap();
+                    let err = IpcError::new(
+                        IpcErrorCode::NotFound,
+                        format!("No window with protocol ID {window_id}!"),
+                    );
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
                 }
             }
+            IpcRequest::WorkspaceWindows { output, index } => {
+                let Some(output) = self.fht.output_named(&output) else {
+                    let err =
+                        IpcError::new(IpcErrorCode::NotFound, format!("No output named {output}!"));
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                    return;
+                };
+
+                let wset = self.fht.wset_for(&output);
+                let index = index.unwrap_or_else(|| wset.get_active_idx());
+                let Some(workspace) = wset.workspaces().nth(index) else {
+                    let err = IpcError::new(
+                        IpcErrorCode::NotFound,
+                        format!("No workspace with index {index}!"),
+                    );
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                    return;
+                };
+
+                let windows = workspace.tiles().map(|tile| tile.element().uid()).collect();
+                to_ipc.send_blocking(IpcResponse::Windows(windows)).unwrap();
+            }
             IpcRequest::GetWindowAppId { window_id } => {
                 if let Some(window) = self
                     .fht
@@ -295,9 +1580,11 @@ impl State {
                         .send_blocking(IpcResponse::WindowPropString(window.app_id()))
                         .unwrap();
                 } else {
-                    to_ipc
-                        .send_blocking(IpcResponse::InvalidProtocolId)
-                        .unwrap();
+                    let err = IpcError::new(
+                        IpcErrorCode::NotFound,
+                        format!("No window with protocol ID {window_id}!"),
+                    );
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
                 }
             }
             IpcRequest::GetWindowMaximized { window_id } => {
@@ -310,9 +1597,11 @@ impl State {
                         .send_blocking(IpcResponse::WindowPropBool(window.maximized()))
                         .unwrap();
                 } else {
-                    to_ipc
-                        .send_blocking(IpcResponse::InvalidProtocolId)
-                        .unwrap();
+                    let err = IpcError::new(
+                        IpcErrorCode::NotFound,
+                        format!("No window with protocol ID {window_id}!"),
+                    );
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
                 }
             }
             IpcRequest::SetWindowMaximized {
@@ -332,15 +1621,391 @@ impl State {
                 window.toplevel().unwrap().send_pending_configure();
                 self.fht.ws_mut_for(&window).unwrap().arrange_tiles();
             }
+            IpcRequest::GetWindowSticky { window_id } => {
+                if let Some(window) = self
+                    .fht
+                    .all_windows()
+                    .find(|window| window.uid() == window_id)
+                {
+                    let sticky = self
+                        .fht
+                        .ws_for(window)
+                        .unwrap()
+                        .tiles()
+                        .find(|tile| tile.element == *window)
+                        .is_some_and(|tile| tile.sticky);
+                    to_ipc
+                        .send_blocking(IpcResponse::WindowPropBool(sticky))
+                        .unwrap();
+                } else {
+                    let err = IpcError::new(
+                        IpcErrorCode::NotFound,
+                        format!("No window with protocol ID {window_id}!"),
+                    );
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                }
+            }
+            IpcRequest::SetWindowSticky { window_id, sticky } => {
+                let Some(window) = self
+                    .fht
+                    .all_windows()
+                    .find(|window| window.uid() == window_id)
+                    .cloned()
+                else {
+                    return;
+                };
+
+                self.fht
+                    .ws_mut_for(&window)
+                    .unwrap()
+                    .set_element_sticky(&window, sticky);
+            }
+            IpcRequest::GetWindowPinned { window_id } => {
+                if let Some(window) = self
+                    .fht
+                    .all_windows()
+                    .find(|window| window.uid() == window_id)
+                {
+                    let pinned = self
+                        .fht
+                        .ws_for(window)
+                        .unwrap()
+                        .tiles()
+                        .find(|tile| tile.element == *window)
+                        .is_some_and(|tile| tile.pinned);
+                    to_ipc
+                        .send_blocking(IpcResponse::WindowPropBool(pinned))
+                        .unwrap();
+                } else {
+                    let err = IpcError::new(
+                        IpcErrorCode::NotFound,
+                        format!("No window with protocol ID {window_id}!"),
+                    );
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                }
+            }
+            IpcRequest::SetWindowPinned { window_id, pinned } => {
+                let Some(window) = self
+                    .fht
+                    .all_windows()
+                    .find(|window| window.uid() == window_id)
+                    .cloned()
+                else {
+                    return;
+                };
+
+                self.fht
+                    .ws_mut_for(&window)
+                    .unwrap()
+                    .set_element_pinned(&window, pinned);
+            }
+            IpcRequest::SetWindowBorder {
+                window_id,
+                overrides,
+            } => {
+                let Some(window) = self
+                    .fht
+                    .all_windows()
+                    .find(|window| window.uid() == window_id)
+                    .cloned()
+                else {
+                    return;
+                };
+
+                self.fht
+                    .ws_mut_for(&window)
+                    .unwrap()
+                    .set_element_border_overrides(&window, overrides);
+                for output in self.fht.outputs() {
+                    OutputState::get(output).render_state.queue();
+                }
+            }
             IpcRequest::SetFocusedOutput { name } => {
                 if let Some(output) = self.fht.output_named(&name) {
-                    if CONFIG.general.cursor_warps {
-                        let center = output.geometry().center();
-                        self.move_pointer(center.to_f64());
+                    self.fht.focus_state.output = Some(output.clone());
+                    self.warp_pointer_to_output(&output);
+                }
+            }
+            IpcRequest::SetOutputAlias { name, alias } => {
+                let Some(output) = self.fht.output_named(&name) else {
+                    let err =
+                        IpcError::new(IpcErrorCode::NotFound, format!("No output named {name}!"));
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                    return;
+                };
+                if alias == "active"
+                    || self.fht.outputs().any(|o| {
+                        *o != output
+                            && (o.name() == alias
+                                || self.fht.output_alias(o).is_some_and(|a| a == alias))
+                    })
+                {
+                    let err = IpcError::new(
+                        IpcErrorCode::InvalidArgument,
+                        format!("Can't use {alias} as an alias, another output already uses it!"),
+                    );
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                    return;
+                }
+
+                if alias.is_empty() {
+                    self.fht.output_aliases.remove(&output.name());
+                } else {
+                    self.fht.output_aliases.insert(output.name(), alias);
+                }
+                self.fht.update_ipc_output_alias(&output);
+                to_ipc.send_blocking(IpcResponse::Done).unwrap();
+            }
+            IpcRequest::SetPrimaryOutput { name } => {
+                let Some(output) = self.fht.output_named(&name) else {
+                    let err =
+                        IpcError::new(IpcErrorCode::NotFound, format!("No output named {name}!"));
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                    return;
+                };
+
+                self.fht.primary_output_override = Some(output.name());
+                self.fht.update_ipc_primary_output();
+                to_ipc.send_blocking(IpcResponse::Done).unwrap();
+            }
+            IpcRequest::FocusLastWorkspace { output } => {
+                let Some(output) = self.fht.output_named(&output) else {
+                    let err =
+                        IpcError::new(IpcErrorCode::NotFound, format!("No output named {output}!"));
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                    return;
+                };
+                let wset = self.fht.wset_mut_for(&output);
+                let new_focus = wset
+                    .get_last_active_idx()
+                    .and_then(|idx| wset.set_active_idx(idx, true));
+                // Don't steal focus from the focused output.
+                if output == self.fht.active_output()
+                    && let Some(window) = new_focus
+                {
+                    self.set_focus_target(Some(window.into()));
+                }
+                to_ipc.send_blocking(IpcResponse::Done).unwrap();
+            }
+            IpcRequest::EvacuateOutput { from, to } => {
+                let Some(from_output) = self.fht.output_named(&from) else {
+                    let err =
+                        IpcError::new(IpcErrorCode::NotFound, format!("No output named {from}!"));
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                    return;
+                };
+                let Some(to_output) = self.fht.output_named(&to) else {
+                    let err =
+                        IpcError::new(IpcErrorCode::NotFound, format!("No output named {to}!"));
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                    return;
+                };
+                if from_output == to_output {
+                    let err = IpcError::new(
+                        IpcErrorCode::InvalidArgument,
+                        "Can't evacuate an output to itself!".to_string(),
+                    );
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                    return;
+                }
+
+                self.fht.evacuate_output(&from_output, &to_output);
+                to_ipc.send_blocking(IpcResponse::Done).unwrap();
+            }
+            IpcRequest::SendKey { keysym, pressed } => {
+                let Some(keysym) = crate::input::keysym_from_name(&keysym) else {
+                    let err = IpcError::new(
+                        IpcErrorCode::InvalidArgument,
+                        format!("Invalid keysym {keysym}!"),
+                    );
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                    return;
+                };
+
+                if !self.send_synthetic_key(keysym, pressed) {
+                    let err = IpcError::new(
+                        IpcErrorCode::NotFound,
+                        "No key produces this keysym in the active layout!",
+                    );
+                    to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                    return;
+                }
+
+                to_ipc.send_blocking(IpcResponse::Done).unwrap();
+            }
+            IpcRequest::SendButton { button, pressed } => {
+                let button = match button.to_lowercase().as_str() {
+                    "left" => FhtMouseButton::Left,
+                    "middle" => FhtMouseButton::Middle,
+                    "right" => FhtMouseButton::Right,
+                    "back" => FhtMouseButton::Back,
+                    "forward" => FhtMouseButton::Forward,
+                    _ => {
+                        let err = IpcError::new(
+                            IpcErrorCode::InvalidArgument,
+                            format!("Invalid button {button}!"),
+                        );
+                        to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                        return;
                     }
-                    self.fht.focus_state.output = Some(output);
+                };
+
+                self.send_synthetic_button(button, pressed);
+                to_ipc.send_blocking(IpcResponse::Done).unwrap();
+            }
+            IpcRequest::SetDebugFlag { flag, enabled } => {
+                self.fht.debug_overrides.set(flag, enabled);
+                for output in self.fht.outputs() {
+                    OutputState::get(output).render_state.queue();
                 }
             }
+            IpcRequest::SetFocusFollowsMouse { enabled } => {
+                self.fht.focus_follows_mouse_override = Some(enabled);
+            }
+            IpcRequest::FindLayerShell { namespace, output } => {
+                let output = match output {
+                    Some(name) => match self.fht.output_named(&name) {
+                        Some(output) => Some(output),
+                        None => {
+                            to_ipc.send_blocking(IpcResponse::LayerShell(None)).unwrap();
+                            return;
+                        }
+                    },
+                    None => None,
+                };
+                let ret = self
+                    .fht
+                    .outputs()
+                    .filter(|o| output.as_ref().map_or(true, |output| output == *o))
+                    .find_map(|o| {
+                        let layer_map = layer_map_for_output(o);
+                        let layer = layer_map
+                            .layers()
+                            .find(|layer| layer.namespace() == namespace)?;
+                        Some((o.name(), layer_name(layer.layer()).to_string()))
+                    });
+
+                to_ipc.send_blocking(IpcResponse::LayerShell(ret)).unwrap();
+            }
+            IpcRequest::WindowAtCursor => {
+                let pointer_loc = self.fht.pointer.current_location().as_global();
+                let output = self.fht.active_output();
+                let window_id = self
+                    .fht
+                    .wset_for(&output)
+                    .element_under(pointer_loc)
+                    .map(|(window, _)| window.uid());
+
+                to_ipc
+                    .send_blocking(IpcResponse::Window(window_id))
+                    .unwrap();
+            }
+            IpcRequest::LayerShellAtCursor => {
+                let pointer_loc = self.fht.pointer.current_location().as_global();
+                let output = self.fht.active_output();
+                let layer_map = layer_map_for_output(&output);
+                let point = pointer_loc.to_local(&output).as_logical();
+                let ret = [Layer::Overlay, Layer::Top, Layer::Bottom, Layer::Background]
+                    .into_iter()
+                    .find_map(|layer| {
+                        let layer_surface = layer_map.layer_under(layer, point)?;
+                        Some((
+                            layer_surface.namespace().to_string(),
+                            output.name(),
+                            layer_name(layer).to_string(),
+                        ))
+                    });
+
+                to_ipc
+                    .send_blocking(IpcResponse::LayerShellInfo(ret))
+                    .unwrap();
+            }
+            IpcRequest::RenderStats { output } => {
+                let target_output = match output {
+                    Some(name) => match self.fht.output_named(&name) {
+                        Some(output) => Some(output),
+                        None => {
+                            let err = IpcError::new(
+                                IpcErrorCode::NotFound,
+                                format!("No output named {name}!"),
+                            );
+                            to_ipc.send_blocking(IpcResponse::Error(err)).unwrap();
+                            return;
+                        }
+                    },
+                    None => None,
+                };
+
+                let stats = self
+                    .fht
+                    .outputs()
+                    .filter(|o| target_output.as_ref().map_or(true, |target| o == target))
+                    .filter_map(|o| {
+                        let stats = self.backend.render_stats(o)?;
+                        let frame_times = stats
+                            .frame_times
+                            .iter()
+                            .map(Duration::as_millis_f64)
+                            .collect();
+                        Some((
+                            o.name(),
+                            frame_times,
+                            stats.avg_presentation_latency.as_millis_f64(),
+                            stats.missed_frames as u32,
+                        ))
+                    })
+                    .collect();
+                to_ipc
+                    .send_blocking(IpcResponse::RenderStats(stats))
+                    .unwrap();
+            }
+            IpcRequest::WindowThumbnail {
+                window_id,
+                max_width,
+                sender,
+            } => {
+                let Some(window) = self
+                    .fht
+                    .all_windows()
+                    .find(|window| window.uid() == window_id)
+                    .cloned()
+                else {
+                    let err = IpcError::new(
+                        IpcErrorCode::NotFound,
+                        format!("No window with protocol ID {window_id}!"),
+                    );
+                    let _ = sender.send_blocking(Err(err));
+                    return;
+                };
+
+                // The window could be on an inactive workspace, so make sure that its output
+                // renders a frame to pick up the thumbnail.
+                let Some(workspace) = self.fht.ws_for(&window) else {
+                    let err = IpcError::new(
+                        IpcErrorCode::NotFound,
+                        format!("The window with protocol ID {window_id} is not on any workspace!"),
+                    );
+                    let _ = sender.send_blocking(Err(err));
+                    return;
+                };
+                OutputState::get(&workspace.output).render_state.queue();
+                self.fht.pending_thumbnails.push(PendingThumbnail {
+                    window,
+                    max_width,
+                    sender,
+                });
+            }
+            IpcRequest::PickWindows { sender } => {
+                if self.fht.pending_window_pick.is_some() {
+                    // Dropping the sender makes the IPC side error out.
+                    return;
+                }
+
+                self.fht.pending_window_pick = Some(PendingWindowPick {
+                    picked: vec![],
+                    sender,
+                });
+            }
         }
     }
 }
@@ -17,6 +17,11 @@ pub struct Output {
     /// The refresh rate of the output.
     pub refresh_rate: f32,
 
+    /// The current mode of the output, as `(width, height, refresh_rate)`.
+    ///
+    /// Unlike `size`, the width and height are in physical pixels, unscaled and untransformed.
+    pub current_mode: (u32, u32, f64),
+
     /// The make/brand of this output's display.
     pub make: String,
 
@@ -32,6 +37,20 @@ pub struct Output {
 
     /// The active workspace index for this output.
     pub active_workspace_index: u8,
+
+    /// Whether this output is the primary output.
+    pub primary: bool,
+
+    /// The color bit depth negotiated for this output, either 8 or 10.
+    pub bit_depth: u8,
+
+    /// Whether this output is enabled.
+    ///
+    /// Disconnected outputs are disabled for a moment before getting removed.
+    pub enabled: bool,
+
+    /// The alias of this output, empty if it has none.
+    pub alias: String,
 }
 
 pub enum Request {
@@ -62,16 +81,30 @@ impl Output {
                 location: (geometry.loc.x, geometry.loc.y),
                 size: (geometry.size.w, geometry.size.h),
                 refresh_rate: mode.refresh as f32 / 1_000.0,
+                current_mode: Self::mode_tuple(mode),
                 make: physical_properties.make,
                 model: physical_properties.model,
                 fractional_scale,
                 integer_scale,
                 active_workspace_index: active_idx as u8,
+                primary: false,
+                bit_depth: 8,
+                enabled: true,
+                alias: String::new(),
             },
             path,
             from_ipc_channel,
         )
     }
+
+    /// Get the IPC representation of an output mode, see [`Output::current_mode`].
+    pub fn mode_tuple(mode: smithay::output::Mode) -> (u32, u32, f64) {
+        (
+            mode.size.w as u32,
+            mode.size.h as u32,
+            mode.refresh as f64 / 1_000.0,
+        )
+    }
 }
 
 #[zbus::interface(name = "fht.desktop.Compositor.Output")]
@@ -96,6 +129,11 @@ impl Output {
         self.refresh_rate
     }
 
+    #[zbus(property)]
+    fn current_mode(&self) -> (u32, u32, f64) {
+        self.current_mode
+    }
+
     #[zbus(property)]
     fn make(&self) -> &str {
         &self.make
@@ -121,6 +159,26 @@ impl Output {
         self.active_workspace_index
     }
 
+    #[zbus(property)]
+    fn primary(&self) -> bool {
+        self.primary
+    }
+
+    #[zbus(property)]
+    fn bit_depth(&self) -> u8 {
+        self.bit_depth
+    }
+
+    #[zbus(property)]
+    fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    #[zbus(property)]
+    fn alias(&self) -> &str {
+        &self.alias
+    }
+
     #[zbus(property)]
     fn set_active_workspace_index(&self, index: u8) {
         if let Err(err) = self
@@ -2,12 +2,26 @@ use smithay::reexports::calloop;
 use zbus::interface;
 
 pub enum Request {
-    ChangeMasterWidthFactor { delta: f32 },
-    ChangeNmaster { delta: i32 },
+    ChangeMasterWidthFactor {
+        delta: f32,
+    },
+    ChangeNmaster {
+        delta: i32,
+    },
+    SetLayoutParams {
+        mwfact: Option<f32>,
+        nmaster: Option<usize>,
+    },
     SelectNextLayout,
     SelectPreviousLayout,
     FocusNextWindow,
     FocusPreviousWindow,
+    ArrangeFloating {
+        sender: async_std::channel::Sender<u32>,
+    },
+    Rename {
+        name: Option<String>,
+    },
 }
 
 pub struct Workspace {
@@ -29,15 +43,23 @@ pub struct Workspace {
     /// The fullscreen window for this workspace, its ID to be exact.
     pub fullscreen: Option<u64>,
 
-    /// The active layout name.
+    /// The active layout name, one of `tile`, `bstack`, `cmaster`, `monocle`, `grid` or
+    /// `floating`.
     pub active_layout: String,
 
     /// Whether this workspace is the focused one on its output.
     pub active: bool,
+
+    /// The name of this workspace, empty if it has none.
+    pub name: String,
 }
 
 impl Workspace {
-    pub fn new(active: bool, active_layout: String) -> (Self, calloop::channel::Channel<Request>) {
+    pub fn new(
+        active: bool,
+        active_layout: String,
+        name: String,
+    ) -> (Self, calloop::channel::Channel<Request>) {
         let (to_compositor, from_ipc_channel) = calloop::channel::channel();
 
         (
@@ -48,6 +70,7 @@ impl Workspace {
                 fullscreen: None,
                 active_layout,
                 active,
+                name,
             },
             from_ipc_channel,
         )
@@ -71,6 +94,20 @@ impl Workspace {
         }
     }
 
+    /// Set the master width factor and nmaster of the active layout at once.
+    ///
+    /// Pass a negative `mwfact` or a zero `nmaster` to keep the current value.
+    async fn set_layout_params(&self, mwfact: f32, nmaster: u32) {
+        let mwfact = (mwfact >= 0.0).then_some(mwfact);
+        let nmaster = (nmaster > 0).then_some(nmaster as usize);
+        if let Err(err) = self
+            .to_compositor
+            .send(Request::SetLayoutParams { mwfact, nmaster })
+        {
+            warn!(?err, "Failed to send IPC request to the compositor!");
+        }
+    }
+
     async fn select_next_layout(&self) {
         if let Err(err) = self.to_compositor.send(Request::SelectNextLayout) {
             warn!(?err, "Failed to send IPC request to the compositor!");
@@ -95,6 +132,34 @@ impl Workspace {
         }
     }
 
+    /// Rename this workspace, an empty name clears it.
+    ///
+    /// This overrides the name from `general.workspace_names` until the config gets reloaded.
+    async fn rename(&self, name: String) {
+        let name = (!name.is_empty()).then_some(name);
+        if let Err(err) = self.to_compositor.send(Request::Rename { name }) {
+            warn!(?err, "Failed to send IPC request to the compositor!");
+        }
+    }
+
+    /// Arrange the floating windows of this workspace in a grid, without tiling them.
+    ///
+    /// Returns the number of arranged windows.
+    async fn arrange_floating(&self) -> zbus::fdo::Result<u32> {
+        let (sender, receiver) = async_std::channel::bounded(1);
+        if let Err(err) = self.to_compositor.send(Request::ArrangeFloating { sender }) {
+            warn!(?err, "Failed to send IPC request to the compositor!");
+            return Err(zbus::fdo::Error::Failed(
+                "Failed to send request to the compositor!".to_string(),
+            ));
+        }
+
+        receiver
+            .recv()
+            .await
+            .map_err(|err| zbus::fdo::Error::Failed(err.to_string()))
+    }
+
     #[zbus(property)]
     async fn windows(&self) -> &[u64] {
         self.windows.as_slice()
@@ -125,4 +190,9 @@ impl Workspace {
     async fn active(&self) -> bool {
         self.active
     }
+
+    #[zbus(property)]
+    async fn name(&self) -> &str {
+        &self.name
+    }
 }
@@ -66,6 +66,36 @@ fn main() -> anyhow::Result<(), Box<dyn Error>> {
         "Starting fht-compositor."
     );
 
+    if std::env::args().any(|arg| arg == "--no-create-config") {
+        // Picked up by the configuration loading, see `fht_config::Config::NO_CREATE_ENV_VAR`
+        std::env::set_var("FHTC_NO_CREATE_CONFIG", "1");
+    }
+
+    if std::env::args().any(|arg| arg == "--lint-config") {
+        config::lint_config()?;
+        return Ok(());
+    }
+
+    // Only one compositor instance can own the IPC service name, so make sure we are alone before
+    // starting anything.
+    match utils::dbus::name_has_owner() {
+        Ok(false) => (),
+        Ok(true) if std::env::args().any(|arg| arg == "--replace") => {
+            info!("Replacing the running fht-compositor instance.");
+            utils::dbus::replace_running_instance()?;
+        }
+        Ok(true) => {
+            error!(
+                "Another fht-compositor instance is already running! Pass --replace to take over."
+            );
+            return Err("Another fht-compositor instance is already running!".into());
+        }
+        Err(err) => warn!(
+            ?err,
+            "Failed to check for a running fht-compositor instance!"
+        ),
+    }
+
     #[cfg(feature = "profile-with-puffin")]
     let _puffin_server = {
         profiling::register_thread!("Main Thread");
@@ -17,6 +17,8 @@ pub mod texture_element;
 
 use glam::Mat3;
 use smithay::backend::allocator::dmabuf::Dmabuf;
+use smithay::backend::allocator::Fourcc;
+use smithay::backend::renderer::damage::OutputDamageTracker;
 use smithay::backend::renderer::element::solid::SolidColorRenderElement;
 use smithay::backend::renderer::element::surface::WaylandSurfaceRenderElement;
 use smithay::backend::renderer::element::{AsRenderElements, RenderElement};
@@ -26,12 +28,14 @@ use smithay::backend::renderer::gles::{
 use smithay::backend::renderer::glow::{GlowFrame, GlowRenderer};
 #[cfg(feature = "udev_backend")]
 use smithay::backend::renderer::multigpu::MultiTexture;
-use smithay::backend::renderer::{Bind, Frame, ImportAll, ImportMem, Offscreen, Renderer, Texture};
-use smithay::desktop::layer_map_for_output;
+use smithay::backend::renderer::{
+    Bind, ExportMem, Frame, ImportAll, ImportMem, Offscreen, Renderer, Texture,
+};
 use smithay::desktop::space::SurfaceTree;
+use smithay::desktop::{layer_map_for_output, Window};
 use smithay::input::pointer::CursorImageStatus;
 use smithay::output::Output;
-use smithay::utils::{IsAlive, Scale};
+use smithay::utils::{IsAlive, Point, Rectangle, Scale, Size, Transform};
 use smithay::wayland::shell::wlr_layer::Layer;
 
 use self::texture_element::FhtTextureElement;
@@ -40,8 +44,10 @@ use crate::backend::udev::UdevRenderError;
 #[cfg(feature = "udev_backend")]
 use crate::backend::udev::{UdevFrame, UdevRenderer};
 use crate::config::CONFIG;
+use crate::ipc::{IpcError, IpcErrorCode};
 use crate::portals::CursorMode;
 use crate::shell::cursor::CursorRenderElement;
+use crate::shell::workspaces::tile::WorkspaceElement;
 use crate::shell::workspaces::WorkspaceSetRenderElement;
 use crate::state::{Fht, OutputState};
 use crate::utils::fps::Fps;
@@ -151,6 +157,10 @@ impl Fht {
             return vec![];
         }
 
+        if self.cursor_hidden_while_typing || self.cursor_hidden_by_touch {
+            return vec![];
+        }
+
         let mut reset = false;
         if let CursorImageStatus::Surface(ref surface) = *cursor_guard {
             reset = !surface.alive();
@@ -210,7 +220,10 @@ impl Fht {
 
         let mut egui = egui.lock().unwrap();
         let time = self.clock.now().into();
-        if !CONFIG.renderer.debug_overlay && !CONFIG.greet && self.last_config_error.is_none() {
+        if !self.debug_overrides.debug_overlay()
+            && !CONFIG.greet
+            && self.last_config_error.is_none()
+        {
             // Even if we are rendering nothing, make sure egui understands we are really doing
             // nothing, because not running the context will make it use the last frame it was
             // drawn.
@@ -229,7 +242,7 @@ impl Fht {
 
             egui.render(
                 |ctx| {
-                    if CONFIG.renderer.debug_overlay {
+                    if self.debug_overrides.debug_overlay() {
                         egui::egui_output_debug_overlay(ctx, output, self, fps);
                     }
 
@@ -253,6 +266,51 @@ impl Fht {
         }
     }
 
+    /// Render the pending window thumbnails of the windows on this output, and send them back to
+    /// the IPC.
+    #[profiling::function]
+    pub fn render_thumbnails<R: FhtRenderer>(&mut self, output: &Output, renderer: &mut R)
+    where
+        WaylandSurfaceRenderElement<R>: RenderElement<R>,
+    {
+        if self.pending_thumbnails.is_empty() {
+            return;
+        }
+
+        let pending_thumbnails = std::mem::take(&mut self.pending_thumbnails);
+        let wset = self.wset_for(output);
+        let mut thumbnails = vec![];
+        let mut still_pending = vec![];
+        for thumbnail in pending_thumbnails {
+            if wset.ws_for(&thumbnail.window).is_some() {
+                thumbnails.push(thumbnail);
+            } else if !thumbnail.window.alive() || self.ws_for(&thumbnail.window).is_none() {
+                // Dead windows and windows that left every workspace (scratchpads, the stash)
+                // won't get rendered, don't keep them pending forever.
+                let err = IpcError::new(
+                    IpcErrorCode::NotFound,
+                    "The window is not on any workspace!",
+                );
+                let _ = thumbnail.sender.send_blocking(Err(err));
+            } else {
+                still_pending.push(thumbnail);
+            }
+        }
+        self.pending_thumbnails = still_pending;
+
+        for thumbnail in thumbnails {
+            let res = render_thumbnail(renderer, &thumbnail.window, thumbnail.max_width)
+                .ok_or_else(|| {
+                    IpcError::new(
+                        IpcErrorCode::NotFound,
+                        "The window has no content to render!",
+                    )
+                });
+            // The receiver is gone if the IPC client went away, nothing to do about it.
+            let _ = thumbnail.sender.send_blocking(res);
+        }
+    }
+
     /// Render and submit screencopy buffers using given renderer.
     #[cfg(feature = "xdg-screencast-portal")]
     #[profiling::function]
@@ -340,6 +398,67 @@ impl Fht {
     }
 }
 
+/// Render a thumbnail of this window, downscaled to be at most `max_width` pixels wide.
+///
+/// This returns the thumbnail width, height, and its pixels in RGBA order, or [`None`] if the
+/// window has nothing to render (yet).
+fn render_thumbnail<R: FhtRenderer>(
+    renderer: &mut R,
+    window: &Window,
+    max_width: u32,
+) -> Option<(u32, u32, Vec<u8>)>
+where
+    WaylandSurfaceRenderElement<R>: RenderElement<R>,
+{
+    let size = window.size();
+    if !window.alive() || size.w <= 0 || size.h <= 0 {
+        return None;
+    }
+
+    let scale = (max_width as f64 / size.w as f64).min(1.0);
+    let thumbnail_size = Size::<i32, smithay::utils::Physical>::from((
+        ((size.w as f64 * scale).round() as i32).max(1),
+        ((size.h as f64 * scale).round() as i32).max(1),
+    ));
+    let scale = Scale::from(scale);
+
+    let SplitRenderElements { normal, popups } =
+        WorkspaceElement::render_elements(window, renderer, Point::default(), scale, 1.0);
+    let elements = popups.into_iter().chain(normal).collect::<Vec<_>>();
+
+    let buffer_size = thumbnail_size.to_logical(1).to_buffer(1, Transform::Normal);
+    let offscreen: GlesRenderbuffer = match renderer.create_buffer(Fourcc::Abgr8888, buffer_size) {
+        Ok(offscreen) => offscreen,
+        Err(err) => {
+            warn!(?err, "Failed to create window thumbnail buffer!");
+            return None;
+        }
+    };
+    if let Err(err) = renderer.bind(offscreen) {
+        warn!(?err, "Failed to bind window thumbnail buffer!");
+        return None;
+    }
+
+    let mut damage_tracker = OutputDamageTracker::new(thumbnail_size, scale, Transform::Normal);
+    if let Err(err) = damage_tracker.render_output(renderer, 0, &elements, [0.0; 4]) {
+        warn!(?err, "Failed to render window thumbnail!");
+        return None;
+    }
+
+    let region = Rectangle::from_loc_and_size((0, 0), buffer_size);
+    let bytes = renderer
+        .copy_framebuffer(region, Fourcc::Abgr8888)
+        .and_then(|mapping| renderer.map_texture(&mapping).map(<[u8]>::to_vec));
+    match bytes {
+        // Abgr8888 is RGBA in memory, since it's little endian.
+        Ok(bytes) => Some((thumbnail_size.w as u32, thumbnail_size.h as u32, bytes)),
+        Err(err) => {
+            warn!(?err, "Failed to copy window thumbnail!");
+            None
+        }
+    }
+}
+
 /// A meta trait combining all the requirements for our renderer
 pub trait FhtRenderer:
     Renderer<TextureId = Self::FhtTextureId, Error = Self::FhtError>
@@ -348,6 +467,7 @@ pub trait FhtRenderer:
     + Bind<Dmabuf>
     + Offscreen<GlesRenderbuffer>
     + Offscreen<GlesTexture>
+    + ExportMem
     + AsGlowRenderer
 {
     // Thank you rust for not being able  to resolve type bounds.
@@ -5,7 +5,7 @@ use smithay::backend::renderer::element::{Element, Id, Kind, RenderElement, Unde
 use smithay::backend::renderer::gles::{GlesError, GlesFrame, Uniform};
 use smithay::backend::renderer::glow::{GlowFrame, GlowRenderer};
 use smithay::backend::renderer::utils::{CommitCounter, DamageSet};
-use smithay::utils::{Buffer, Logical, Physical, Point, Rectangle, Scale, Size, Transform};
+use smithay::utils::{Buffer, Logical, Physical, Point, Rectangle, Scale, Transform};
 
 use super::shaders::Shaders;
 use super::AsGlowFrame;
@@ -16,7 +16,8 @@ use crate::backend::udev::{UdevFrame, UdevRenderError, UdevRenderer};
 #[derive(Debug)]
 pub struct RoundedCornerElement<E: Element> {
     element: E,
-    corner_radius: f32,
+    /// The radius of each corner: top-left, top-right, bottom-right, bottom-left.
+    corner_radius: [f32; 4],
     input_to_geo: Mat3,
     // where is the rounded rectangle that is going to contain everything.
     geo: Rectangle<i32, Logical>,
@@ -26,7 +27,7 @@ impl<E: Element> RoundedCornerElement<E> {
     /// Create a new rounded corner element
     pub fn new(
         element: E,
-        corner_radius: f32,
+        corner_radius: [f32; 4],
         geometry: Rectangle<i32, Logical>,
         scale: Scale<f64>,
     ) -> Self {
@@ -70,14 +71,14 @@ impl<E: Element> RoundedCornerElement<E> {
         elem: &E,
         scale: Scale<f64>,
         geometry: Rectangle<i32, Logical>,
-        corner_radius: f32,
+        corner_radius: [f32; 4],
     ) -> bool {
         let elem_geo = elem.geometry(scale);
         let geo = geometry.to_physical_precise_round(scale);
 
         // In case our corner radius is 0.0, we just want to see if we can hold this
         // surface in the render geometry. (no rounded corners)
-        if corner_radius == 0.0 {
+        if corner_radius == [0.0; 4] {
             !geo.contains_rect(elem_geo)
         } else {
             // Now we have our rounded corners, so we gotta calc where our rounded corners will
@@ -97,7 +98,7 @@ impl<E: Element> RoundedCornerElement<E> {
 
     /// Calculate rounded corners non-opaque regions.
     pub fn rounded_corners_regions(
-        corner_radius: f32,
+        corner_radius: [f32; 4],
         geo: Rectangle<i32, Logical>,
         scale: Scale<f64>,
     ) -> [Rectangle<i32, Physical>; 4] {
@@ -111,28 +112,28 @@ impl<E: Element> RoundedCornerElement<E> {
 
         // Even if we round and get up a little more, its no big deal if the ORs are offset by one
         // pixel or two.
-        let corner_radius = corner_radius.clamp(0.0, f32::INFINITY).round() as i32;
-        let corner_radius_size: Size<_, _> = (corner_radius, corner_radius).into();
+        let [top_left, top_right, bottom_right, bottom_left] =
+            corner_radius.map(|radius| radius.clamp(0.0, f32::INFINITY).round() as i32);
 
         [
-            Rectangle::from_loc_and_size(geo.loc, corner_radius_size)
+            Rectangle::from_loc_and_size(geo.loc, (top_left, top_left))
                 .to_physical_precise_round(scale), // top left
             Rectangle::from_loc_and_size(
-                (geo.loc.x + geo.size.w - corner_radius, geo.loc.y),
-                corner_radius_size,
+                (geo.loc.x + geo.size.w - top_right, geo.loc.y),
+                (top_right, top_right),
             )
             .to_physical_precise_round(scale), // top right
             Rectangle::from_loc_and_size(
                 (
-                    geo.loc.x + geo.size.w - corner_radius,
-                    geo.loc.y + geo.size.h - corner_radius,
+                    geo.loc.x + geo.size.w - bottom_right,
+                    geo.loc.y + geo.size.h - bottom_right,
                 ),
-                corner_radius_size,
+                (bottom_right, bottom_right),
             )
             .to_physical_precise_round(scale), // bottom right
             Rectangle::from_loc_and_size(
-                (geo.loc.x, geo.loc.y + geo.size.h - corner_radius),
-                corner_radius_size,
+                (geo.loc.x, geo.loc.y + geo.size.h - bottom_left),
+                (bottom_left, bottom_left),
             )
             .to_physical_precise_round(scale), // bottom left
         ]
@@ -183,7 +184,7 @@ impl<E: Element> Element for RoundedCornerElement<E> {
             .filter_map(|rect| rect.intersection(geo));
 
         // We are not clipping anything.
-        if self.corner_radius == 0.0 {
+        if self.corner_radius == [0.0; 4] {
             return regions.collect();
         }
 
@@ -219,7 +220,7 @@ where
         dst: Rectangle<i32, Physical>,
         damage: &[Rectangle<i32, Physical>],
     ) -> Result<(), GlesError> {
-        if self.corner_radius == 0.0 {
+        if self.corner_radius == [0.0; 4] {
             self.element.draw(frame, src, dst, damage)
         } else {
             // Override texture shader with our uniforms
@@ -260,7 +261,7 @@ where
         dst: Rectangle<i32, Physical>,
         damage: &[Rectangle<i32, Physical>],
     ) -> Result<(), UdevRenderError<'a>> {
-        if self.corner_radius == 0.0 {
+        if self.corner_radius == [0.0; 4] {
             self.element.draw(frame, src, dst, damage)
         } else {
             // Override texture shader with our uniforms
@@ -23,7 +23,8 @@ uniform float tint;
 #endif
 
 uniform vec2 geo_size;
-uniform float corner_radius;
+// The radius of each corner: top-left, top-right, bottom-right, bottom-left.
+uniform vec4 corner_radius;
 uniform mat3 input_to_geo;
 
 float rounding_alpha(vec2 coords, vec2 size) {
@@ -33,19 +34,19 @@ float rounding_alpha(vec2 coords, vec2 size) {
     // from the corner radius.
     //
     // Hacky? Yeah, can't do anything abt it.
-    float corner_radius = corner_radius - 2.;
+    vec4 corner_radius = corner_radius - 2.;
 
-    if (coords.x < corner_radius && coords.y < corner_radius) {
-        radius = corner_radius;
+    if (coords.x < corner_radius.x && coords.y < corner_radius.x) {
+        radius = corner_radius.x;
         center = vec2(radius, radius);
-    } else if (size.x - corner_radius < coords.x && coords.y < corner_radius) {
-        radius = corner_radius;
+    } else if (size.x - corner_radius.y < coords.x && coords.y < corner_radius.y) {
+        radius = corner_radius.y;
         center = vec2(size.x - radius, radius);
-    } else if (size.x - corner_radius < coords.x && size.y - corner_radius < coords.y) {
-        radius = corner_radius;
+    } else if (size.x - corner_radius.z < coords.x && size.y - corner_radius.z < coords.y) {
+        radius = corner_radius.z;
         center = vec2(size.x - radius, size.y - radius);
-    } else if (coords.x < corner_radius && size.y - corner_radius < coords.y) {
-        radius = corner_radius;
+    } else if (coords.x < corner_radius.w && size.y - corner_radius.w < coords.y) {
+        radius = corner_radius.w;
         center = vec2(radius, size.y - radius);
     } else {
         return 1.0;
@@ -14,8 +14,8 @@ pub struct RoundedOutlineSettings {
     /// The half thickness to use.
     /// The shader uses this anyway
     pub half_thickness: f32,
-    /// The radius.
-    pub radius: f32,
+    /// The radius of each corner: top-left, top-right, bottom-right, bottom-left.
+    pub radius: [f32; 4],
     /// The color, either a solid one or a gradient.
     pub color: ColorConfig,
 }
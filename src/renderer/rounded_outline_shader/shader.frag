@@ -3,13 +3,22 @@ precision mediump float;
 uniform vec4 v_start_color;
 uniform vec4 v_end_color;
 uniform float v_gradient_angle;
-uniform float radius;
+// The radius of each corner: top-left, top-right, bottom-right, bottom-left.
+uniform vec4 radius;
 uniform float half_thickness;
 
 uniform vec2 size;
 uniform float alpha;
 varying vec2 v_coords;
 
+// Pick the radius of the corner this point is closest to.
+float cornerRadius(vec2 center, vec4 radius) {
+    if (center.x < 0.0) {
+        return center.y < 0.0 ? radius.x : radius.w;
+    }
+    return center.y < 0.0 ? radius.y : radius.z;
+}
+
 float roundedBoxSDF(vec2 center, vec2 size, float radius) {
     vec2 q = abs(center) - size + radius;
     return min(max(q.x,q.y),0.0) + length(max(q,0.0)) - radius;
@@ -35,7 +44,8 @@ void main() {
     vec2 coords = v_coords * size;
     vec2 center = coords - half_size;
 
-    float distance = roundedBoxSDF(center, half_size - vec2(half_thickness), radius - half_thickness);
+    float corner_radius = cornerRadius(center, radius);
+    float distance = roundedBoxSDF(center, half_size - vec2(half_thickness), corner_radius - half_thickness);
     float smoothedAlphaOuter = 1.0 - smoothstep(-0.5, .5, distance - half_thickness);
     // Create an inner circle that isn't as anti-aliased as the outer ring
     float smoothedAlphaInner = 1.0 - smoothstep(-0.5, 0.25, distance + half_thickness);
@@ -29,7 +29,7 @@ impl Shaders {
             .compile_custom_texture_shader(
                 ROUNDED_QUAD_SRC,
                 &[
-                    UniformName::new("corner_radius", UniformType::_1f),
+                    UniformName::new("corner_radius", UniformType::_4f),
                     UniformName::new("geo_size", UniformType::_2f),
                     UniformName::new("input_to_geo", UniformType::Matrix3x3),
                 ],
@@ -42,7 +42,7 @@ impl Shaders {
                     UniformName::new("v_start_color", UniformType::_4f),
                     UniformName::new("v_end_color", UniformType::_4f),
                     UniformName::new("v_gradient_angle", UniformType::_1f),
-                    UniformName::new("radius", UniformType::_1f),
+                    UniformName::new("radius", UniformType::_4f),
                     UniformName::new("half_thickness", UniformType::_1f),
                 ],
             )
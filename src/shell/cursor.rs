@@ -68,7 +68,7 @@ pub struct CursorThemeManager {
 impl CursorThemeManager {
     /// Initialize the cursor theme manager.
     pub fn new() -> Self {
-        let CursorConfig { name, size } = CONFIG.general.cursor.clone();
+        let CursorConfig { name, size, .. } = CONFIG.general.cursor.clone();
         let image_status = CursorImageStatus::default_named();
         let cursor_theme = CursorTheme::load(&name);
 
@@ -92,7 +92,7 @@ impl CursorThemeManager {
     /// This is only effective if the name or size have changed.
     #[profiling::function]
     pub fn reload(&mut self) {
-        let CursorConfig { name, size } = CONFIG.general.cursor.clone();
+        let CursorConfig { name, size, .. } = CONFIG.general.cursor.clone();
         if self.cursor_theme_name == name && self.cursor_theme_size == size {
             return;
         }
@@ -1,6 +1,7 @@
 pub mod cursor;
 pub mod focus_target;
 pub mod grabs;
+pub mod session;
 pub mod window;
 pub mod workspaces;
 
@@ -22,14 +23,29 @@ use smithay::reexports::wayland_protocols::xdg::decoration::zv1::server::zxdg_to
 pub use self::focus_target::{KeyboardFocusTarget, PointerFocusTarget};
 use self::grabs::MoveSurfaceGrab;
 use self::workspaces::tile::{WorkspaceElement, WorkspaceTile};
-use self::workspaces::{Workspace, WorkspaceSwitchAnimation};
+use self::workspaces::{SnapRegion, Workspace, WorkspaceSwitchAnimation};
 use crate::config::CONFIG;
 use crate::state::{Fht, UnmappedTile};
 use crate::utils::geometry::{
-    Global, PointExt, PointGlobalExt, PointLocalExt, RectCenterExt, RectExt, RectGlobalExt, RectLocalExt,
+    Global, PointExt, PointGlobalExt, PointLocalExt, RectCenterExt, RectExt, RectGlobalExt,
+    RectLocalExt,
 };
 use crate::utils::output::OutputExt;
 
+/// Whether a window should get focused when it gets mapped.
+///
+/// `focus_rule` is the `focus` setting from the window rules, overriding `focus_new_windows`.
+/// Windows mapped while switching workspaces always get focused, but only windows on the active
+/// workspace can get focused.
+fn should_focus_mapped_window(
+    focus_rule: Option<bool>,
+    focus_new_windows: bool,
+    is_active: bool,
+    is_switching: bool,
+) -> bool {
+    (focus_rule.unwrap_or(focus_new_windows) || is_switching) && is_active
+}
+
 impl Fht {
     /// Get the [`FocusTarget`] under the cursor.
     ///
@@ -250,7 +266,7 @@ impl Fht {
             )
         });
 
-        let map_settings = CONFIG
+        let mut map_settings = CONFIG
             .rules
             .iter()
             .find(|(rules, _)| {
@@ -260,6 +276,26 @@ impl Fht {
             })
             .map(|(_, settings)| settings.clone())
             .unwrap_or_default();
+        if map_settings.floating.is_none() {
+            let always_float = CONFIG
+                .general
+                .always_float
+                .iter()
+                .any(|regex| regex.is_match(&app_id));
+            map_settings.floating = Some(always_float);
+        }
+
+        // A restored session takes precedence over the window rules for placement.
+        let session_window = self.take_session_window(&window);
+        if let Some(saved) = session_window.as_ref() {
+            map_settings.output = Some(saved.output.clone());
+            map_settings.workspace = Some(saved.workspace);
+            map_settings.workspace_name = None;
+            map_settings.floating = Some(saved.floating);
+        }
+        let session_geometry = session_window
+            .filter(|saved| saved.floating)
+            .map(|saved| saved.geometry());
 
         // Apply rules
         //
@@ -268,8 +304,7 @@ impl Fht {
         if let Some(target_output) = map_settings
             .output
             .as_ref()
-            .and_then(|name| self.outputs().find(|o| o.name().as_str() == name))
-            .cloned()
+            .and_then(|name| self.output_named(name))
         {
             output = target_output;
         }
@@ -277,8 +312,20 @@ impl Fht {
         let wset = self.wset_mut_for(&output);
         let mut workspace_idx = match map_settings.workspace {
             None => wset.get_active_idx(),
-            Some(idx) => idx.clamp(0, 9),
+            Some(idx) => idx.min(wset.workspaces.len() - 1),
         };
+        if let Some(name) = map_settings.workspace_name.as_ref() {
+            workspace_idx = match wset
+                .workspaces()
+                .position(|ws| ws.name.as_ref() == Some(name))
+            {
+                Some(idx) => idx,
+                None => {
+                    warn!(?name, "No workspace with this name, using the active one.");
+                    wset.get_active_idx()
+                }
+            };
+        }
 
         // Even if the user set rules, we still always prefer the output and workspace of this
         // window's toplevel parent.
@@ -341,6 +388,8 @@ impl Fht {
             inner: tile,
             last_output: Some(output),
             last_workspace_idx: Some(workspace_idx),
+            map_settings,
+            session_geometry,
         })
     }
 
@@ -355,6 +404,8 @@ impl Fht {
             inner: tile,
             last_output,
             last_workspace_idx,
+            map_settings,
+            session_geometry,
         } = unmapped_tile;
         let wl_surface = tile.element().wl_surface().unwrap();
         let output = last_output.unwrap_or_else(|| self.active_output());
@@ -363,20 +414,50 @@ impl Fht {
         let workspace_idx = last_workspace_idx.unwrap_or(active_idx);
 
         let is_active = workspace_idx == wset.get_active_idx();
+        // From using the compositor opening a window when a switch is being done feels more
+        // natural when the window gets focus, even if focus_new_windows is none.
+        let is_switching = wset.switch_animation.is_some();
         let workspace = &mut wset.workspaces[workspace_idx];
 
+        // Focus rules are evaluated now, at map time, against the window that is focused right
+        // before inserting the new one.
+        let previous_focus = workspace.focused().cloned();
+        let should_focus = should_focus_mapped_window(
+            map_settings.focus,
+            CONFIG.general.focus_new_windows,
+            is_active,
+            is_switching,
+        );
+
         let window = tile.element.clone();
         workspace.insert_tile(tile);
+        if map_settings.floating == Some(true) {
+            workspace.set_element_floating(&window, true);
+        }
+        // A restored session geometry takes precedence over centering.
+        let centered = map_settings
+            .centered
+            .unwrap_or(CONFIG.general.center_new_floating);
+        if centered && session_geometry.is_none() {
+            workspace.snap_element(&window, SnapRegion::Center);
+        }
 
         let tile = workspace.find_tile(&wl_surface).unwrap();
+        if let Some(geometry) = session_geometry
+            && tile.floating
+        {
+            tile.set_geometry_excluding_border(geometry);
+        }
         // we dont want to animate the tile now.
         tile.location_animation.take();
+        tile.input_overrides = map_settings.input_overrides();
         let tile_geo = tile.geometry().to_global(&output);
 
-        // From using the compositor opening a window when a switch is being done feels more
-        // natural when the window gets focus, even if focus_new_windows is none.
-        let is_switching = wset.switch_animation.is_some();
-        let should_focus = (CONFIG.general.focus_new_windows || is_switching) && is_active;
+        if should_focus {
+            workspace.focus_element(&window);
+        } else if let Some(previous_focus) = previous_focus.as_ref() {
+            workspace.focus_element(previous_focus);
+        }
 
         if should_focus {
             let center = tile_geo.center();
@@ -510,3 +591,27 @@ impl crate::state::State {
         pointer.set_grab(self, grab, serial, Focus::Clear);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::should_focus_mapped_window;
+
+    #[test]
+    fn focus_rule_overrides_focus_new_windows() {
+        assert!(should_focus_mapped_window(None, true, true, false));
+        assert!(!should_focus_mapped_window(None, false, true, false));
+        assert!(should_focus_mapped_window(Some(true), false, true, false));
+        assert!(!should_focus_mapped_window(Some(false), true, true, false));
+    }
+
+    #[test]
+    fn focuses_windows_mapped_while_switching() {
+        assert!(should_focus_mapped_window(Some(false), false, true, true));
+    }
+
+    #[test]
+    fn never_focuses_windows_on_inactive_workspaces() {
+        assert!(!should_focus_mapped_window(Some(true), true, false, false));
+        assert!(!should_focus_mapped_window(Some(true), true, false, true));
+    }
+}
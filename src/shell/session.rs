@@ -0,0 +1,125 @@
+//! Best-effort window session persistence.
+//!
+//! The compositor can save the placement of every window to a file, and restore it later on
+//! windows that get mapped with the same app_id and title. This is not full session management:
+//! the windows still have to be launched by the user, they only get put back where they were.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use smithay::desktop::Window;
+use smithay::utils::Rectangle;
+
+use super::workspaces::tile::WorkspaceElement;
+use crate::state::Fht;
+use crate::utils::geometry::Local;
+
+/// How long saved windows are kept around after restoring a session.
+///
+/// Windows mapped after this don't get restored anymore.
+const RESTORE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The saved placement of a window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionWindow {
+    pub app_id: String,
+    pub title: String,
+    /// The name of the output the window was on.
+    pub output: String,
+    /// The index of the workspace the window was on.
+    pub workspace: usize,
+    pub floating: bool,
+    /// The window geometry, relative to its output, as `(x, y, width, height)`.
+    pub geometry: (i32, i32, i32, i32),
+}
+
+impl SessionWindow {
+    /// Get the saved geometry of this window.
+    pub fn geometry(&self) -> Rectangle<i32, Local> {
+        let (x, y, w, h) = self.geometry;
+        Rectangle::from_loc_and_size((x, y), (w, h))
+    }
+}
+
+/// Saved windows waiting to be restored.
+#[derive(Debug)]
+pub struct PendingSessionRestore {
+    windows: Vec<SessionWindow>,
+    deadline: Instant,
+}
+
+impl Fht {
+    /// Save the placement of every window to this file.
+    ///
+    /// Returns the number of saved windows.
+    pub fn save_session(&self, path: &Path) -> anyhow::Result<usize> {
+        let windows = self
+            .workspaces()
+            .flat_map(|(output, wset)| {
+                wset.workspaces().flat_map(move |workspace| {
+                    workspace.tiles().map(move |tile| {
+                        let geometry = tile.geometry();
+                        SessionWindow {
+                            app_id: tile.element().app_id(),
+                            title: tile.element().title(),
+                            output: output.name(),
+                            workspace: workspace.index,
+                            floating: tile.floating,
+                            geometry: (
+                                geometry.loc.x,
+                                geometry.loc.y,
+                                geometry.size.w,
+                                geometry.size.h,
+                            ),
+                        }
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let contents = ron::ser::to_string_pretty(&windows, ron::ser::PrettyConfig::default())
+            .context("Failed to serialize session!")?;
+        std::fs::write(path, contents).context("Failed to write session file!")?;
+
+        Ok(windows.len())
+    }
+
+    /// Load the windows saved in this file, to restore them as they get mapped.
+    ///
+    /// This replaces any previously pending restore. Returns the number of loaded windows.
+    pub fn restore_session(&mut self, path: &Path) -> anyhow::Result<usize> {
+        let contents = std::fs::read_to_string(path).context("Failed to read session file!")?;
+        let windows: Vec<SessionWindow> =
+            ron::from_str(&contents).context("Failed to parse session file!")?;
+        let count = windows.len();
+        self.session_restore = Some(PendingSessionRestore {
+            windows,
+            deadline: Instant::now() + RESTORE_TIMEOUT,
+        });
+
+        Ok(count)
+    }
+
+    /// Take the saved placement of this window out of the pending restore, if any.
+    pub fn take_session_window(&mut self, window: &Window) -> Option<SessionWindow> {
+        let restore = self.session_restore.as_mut()?;
+        if restore.deadline < Instant::now() {
+            self.session_restore = None;
+            return None;
+        }
+
+        let (app_id, title) = (window.app_id(), window.title());
+        let idx = restore
+            .windows
+            .iter()
+            .position(|saved| saved.app_id == app_id && saved.title == title)?;
+        let saved = restore.windows.remove(idx);
+        if restore.windows.is_empty() {
+            self.session_restore = None;
+        }
+
+        Some(saved)
+    }
+}
@@ -38,6 +38,16 @@ pub enum WorkspaceLayout {
         nmaster: usize,
         master_width_factor: f32,
     },
+    /// Monocle layout, where every window takes the whole area.
+    ///
+    /// Only the focused window is visible, you cycle through them by focusing the next and
+    /// previous windows.
+    Monocle,
+    /// Grid layout, where every window gets an equal cell of a roughly square grid.
+    ///
+    /// There's no master/stack distinction, so changing the master width factor or nmaster does
+    /// nothing with this layout. The last row gets stretched if it's not full.
+    Grid,
     /// Floating layout, basically do nothing to arrange the windows.
     Floating,
 }
@@ -275,11 +285,60 @@ impl WorkspaceLayout {
                     }
                 }
             }
+            WorkspaceLayout::Monocle => {
+                for tile in tiles {
+                    tile.set_geometry(tile_area);
+                    tile.send_pending_configure();
+                }
+            }
+            WorkspaceLayout::Grid => {
+                for (tile, geo) in tiles
+                    .into_iter()
+                    .zip(grid_cells(tiles_len, tile_area, inner_gaps))
+                {
+                    tile.set_geometry(geo);
+                    tile.send_pending_configure();
+                }
+            }
             WorkspaceLayout::Floating => {}
         }
     }
 }
 
+/// Split this area in `count` cells of a roughly square grid, with `ceil(sqrt(count))` columns.
+///
+/// The cells are ordered row by row, and the last row cells share its whole width if it's not full.
+pub fn grid_cells(
+    count: usize,
+    area: Rectangle<i32, Local>,
+    inner_gaps: i32,
+) -> Vec<Rectangle<i32, Local>> {
+    if count == 0 {
+        return vec![];
+    }
+
+    let columns = (count as f64).sqrt().ceil() as usize;
+    let rows = count.div_ceil(columns);
+    let cell_height = (area.size.h - inner_gaps * (rows as i32 - 1)) / rows as i32;
+
+    let mut cells = Vec::with_capacity(count);
+    for row in 0..rows {
+        let row_len = min(columns, count - row * columns) as i32;
+        let cell_width = (area.size.w - inner_gaps * (row_len - 1)) / row_len;
+        let y = area.loc.y + row as i32 * (cell_height + inner_gaps);
+
+        for column in 0..row_len {
+            let x = area.loc.x + column * (cell_width + inner_gaps);
+            cells.push(Rectangle::from_loc_and_size(
+                (x, y),
+                (cell_width, cell_height),
+            ));
+        }
+    }
+
+    cells
+}
+
 fn get_facts<'a, E: WorkspaceElement + 'a>(
     tiles: &'a [&'a mut WorkspaceTile<E>],
     nmaster: usize,
@@ -307,3 +366,49 @@ fn get_facts<'a, E: WorkspaceElement + 'a>(
 
     (mfacts, sfacts, msize - mtotal, ssize - stotal)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(x: i32, y: i32, w: i32, h: i32) -> Rectangle<i32, Local> {
+        Rectangle::from_loc_and_size((x, y), (w, h))
+    }
+
+    #[test]
+    fn grid_of_four_is_square() {
+        let area = cell(10, 20, 1010, 610);
+        assert_eq!(
+            grid_cells(4, area, 10),
+            vec![
+                cell(10, 20, 500, 300),
+                cell(520, 20, 500, 300),
+                cell(10, 330, 500, 300),
+                cell(520, 330, 500, 300),
+            ]
+        );
+    }
+
+    #[test]
+    fn grid_last_row_shares_its_width() {
+        // 5 tiles, 3 columns, the second row only has 2 cells.
+        let area = cell(0, 0, 920, 610);
+        assert_eq!(
+            grid_cells(5, area, 10),
+            vec![
+                cell(0, 0, 300, 300),
+                cell(310, 0, 300, 300),
+                cell(620, 0, 300, 300),
+                cell(0, 310, 455, 300),
+                cell(465, 310, 455, 300),
+            ]
+        );
+    }
+
+    #[test]
+    fn grid_of_one_fills_the_area() {
+        let area = cell(0, 0, 800, 600);
+        assert_eq!(grid_cells(1, area, 10), vec![area]);
+        assert!(grid_cells(0, area, 10).is_empty());
+    }
+}
@@ -1,22 +1,24 @@
 pub mod layout;
 pub mod tile;
 
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_std::task::spawn;
+use serde::{Deserialize, Serialize};
 use smithay::backend::renderer::element::utils::{Relocate, RelocateRenderElement};
 use smithay::desktop::{layer_map_for_output, WindowSurfaceType};
 use smithay::output::Output;
 use smithay::reexports::calloop::{self, LoopHandle, RegistrationToken};
 use smithay::reexports::wayland_server::protocol::wl_surface::WlSurface;
-use smithay::utils::{Physical, Point, Rectangle, Scale};
+use smithay::utils::{Physical, Point, Rectangle, Scale, Size};
 
 pub use self::layout::WorkspaceLayout;
 use self::tile::{WorkspaceElement, WorkspaceTile, WorkspaceTileRenderElement};
 use crate::config::{
-    BorderConfig, InsertWindowStrategy, WorkspaceSwitchAnimationDirection, CONFIG,
+    BorderConfig, BorderOverrides, InsertWindowStrategy, WorkspaceSwitchAnimationDirection, CONFIG,
 };
 use crate::fht_render_elements;
 use crate::ipc::{IpcOutput, IpcWorkspace, IpcWorkspaceRequest};
@@ -25,7 +27,8 @@ use crate::state::State;
 use crate::utils::animation::Animation;
 use crate::utils::dbus::DBUS_CONNECTION;
 use crate::utils::geometry::{
-    Global, PointGlobalExt, PointLocalExt, RectExt, RectGlobalExt, RectLocalExt, SizeExt,
+    Global, Local, PointGlobalExt, PointLocalExt, RectCenterExt, RectExt, RectGlobalExt,
+    RectLocalExt, SizeExt,
 };
 use crate::utils::output::OutputExt;
 
@@ -41,6 +44,9 @@ pub struct WorkspaceSet<E: WorkspaceElement> {
 
     /// The active workspace index.
     pub(super) active_idx: AtomicUsize,
+
+    /// The previously active workspace index, if any.
+    last_active_idx: Option<usize>,
 }
 
 #[allow(dead_code)]
@@ -73,24 +79,71 @@ impl<E: WorkspaceElement> WorkspaceSet<E> {
             workspaces,
             switch_animation: None,
             active_idx: 0.into(),
+            last_active_idx: None,
         }
     }
 
     /// Refresh internal state of the [`WorkspaceSet`]
     ///
     /// Preferably call this before flushing clients.
-    pub fn refresh(&mut self) {
+    ///
+    /// If `general.switch_back_on_empty` is enabled and the active workspace just became empty,
+    /// switch back to the previously active workspace, returning the possible focus candidate
+    /// that the compositor should focus.
+    pub fn refresh(&mut self) -> Option<E> {
+        let active_idx = self.get_active_idx();
+        let was_empty = self.workspaces[active_idx].tiles.is_empty();
         self.workspaces_mut().for_each(Workspace::refresh);
+
+        if !CONFIG.general.switch_back_on_empty
+            || was_empty
+            || active_idx == 0
+            || !self.workspaces[active_idx].tiles.is_empty()
+        {
+            return None;
+        }
+
+        let last_active_idx = self.last_active_idx?;
+        if self.workspaces[last_active_idx].tiles.is_empty() {
+            return None;
+        }
+
+        self.set_active_idx(last_active_idx, true)
     }
 
     /// Reload the configuration of the [`WorkspaceSet`]
     pub fn reload_config(&mut self) {
         let layouts = CONFIG.general.layouts.clone();
         for workspace in &mut self.workspaces {
+            workspace.set_name(CONFIG.general.workspace_names.get(workspace.index).cloned());
             workspace.layouts = layouts.clone();
             workspace.active_layout_idx = workspace
                 .active_layout_idx
                 .clamp(0, workspace.layouts.len() - 1);
+            for tile in &mut workspace.tiles {
+                tile.border_overrides = BorderOverrides::default();
+            }
+
+            {
+                let ipc_path = workspace.ipc_path.clone();
+                let layout = workspace.get_active_layout().to_string();
+                spawn(async move {
+                    let iface_ref = DBUS_CONNECTION
+                        .object_server()
+                        .inner()
+                        .interface::<_, IpcWorkspace>(ipc_path.as_ref())
+                        .await
+                        .unwrap();
+                    let mut iface = iface_ref.get_mut().await;
+                    if iface.active_layout != layout {
+                        iface.active_layout = layout;
+                        iface
+                            .active_layout_changed(iface_ref.signal_context())
+                            .await
+                            .unwrap();
+                    }
+                });
+            }
         }
     }
 
@@ -99,9 +152,79 @@ impl<E: WorkspaceElement> WorkspaceSet<E> {
     ///
     /// Animations are opt-in, set `animate` to true if its needed.
     pub fn set_active_idx(&mut self, target_idx: usize, animate: bool) -> Option<E> {
-        let target_idx = target_idx.clamp(0, 9);
+        let forward = target_idx > self.active_idx.load(Ordering::SeqCst);
+        self.switch_to(target_idx, animate, forward)
+    }
+
+    /// Focus the next workspace, returning the possible focus candidate that the compositor
+    /// should focus.
+    ///
+    /// If `general.workspace_switch_wraps` is enabled, going past the last workspace focuses the
+    /// first one, otherwise nothing happens.
+    pub fn focus_next_workspace(&mut self, animate: bool) -> Option<E> {
+        let active_idx = self.get_active_idx();
+        let target_idx = if active_idx + 1 < self.workspaces.len() {
+            active_idx + 1
+        } else if CONFIG.general.workspace_switch_wraps {
+            0
+        } else {
+            return None;
+        };
+        self.switch_to(target_idx, animate, true)
+    }
+
+    /// Focus the previous workspace, returning the possible focus candidate that the compositor
+    /// should focus.
+    ///
+    /// If `general.workspace_switch_wraps` is enabled, going before the first workspace focuses
+    /// the last one, otherwise nothing happens.
+    pub fn focus_previous_workspace(&mut self, animate: bool) -> Option<E> {
+        let active_idx = self.get_active_idx();
+        let target_idx = match active_idx.checked_sub(1) {
+            Some(idx) => idx,
+            None if CONFIG.general.workspace_switch_wraps => self.workspaces.len() - 1,
+            None => return None,
+        };
+        self.switch_to(target_idx, animate, false)
+    }
+
+    /// Get the index of the workspace `offset` workspaces away from the active one.
+    ///
+    /// If `general.workspace_switch_wraps` is enabled, this wraps around, otherwise it stops at
+    /// the first and last workspaces.
+    pub fn relative_workspace_idx(&self, offset: i32) -> usize {
+        let len = self.workspaces.len() as i32;
+        let target_idx = self.get_active_idx() as i32 + offset;
+        if CONFIG.general.workspace_switch_wraps {
+            target_idx.rem_euclid(len) as usize
+        } else {
+            target_idx.clamp(0, len - 1) as usize
+        }
+    }
+
+    /// Focus the workspace `offset` workspaces away from the active one, returning the possible
+    /// focus candidate that the compositor should focus.
+    ///
+    /// See [`WorkspaceSet::relative_workspace_idx`]
+    pub fn focus_workspace_relative(&mut self, offset: i32, animate: bool) -> Option<E> {
+        let target_idx = self.relative_workspace_idx(offset);
+        if target_idx == self.get_active_idx() {
+            return None;
+        }
+        self.switch_to(target_idx, animate, offset > 0)
+    }
+
+    /// Switch to the workspace at `target_idx`, see [`WorkspaceSet::set_active_idx`]
+    ///
+    /// `forward` decides the direction of the switch animation.
+    fn switch_to(&mut self, target_idx: usize, animate: bool, forward: bool) -> Option<E> {
+        let target_idx = target_idx.min(self.workspaces.len() - 1);
         if !animate {
-            self.active_idx.store(target_idx, Ordering::SeqCst);
+            let active_idx = self.active_idx.swap(target_idx, Ordering::SeqCst);
+            if active_idx != target_idx {
+                self.last_active_idx = Some(active_idx);
+                self.move_sticky_tiles(active_idx, target_idx);
+            }
             return self.workspaces[target_idx].focused().cloned();
         }
 
@@ -109,6 +232,8 @@ impl<E: WorkspaceElement> WorkspaceSet<E> {
         if target_idx == active_idx || self.switch_animation.is_some() {
             return None;
         }
+        self.last_active_idx = Some(active_idx);
+        self.move_sticky_tiles(active_idx, target_idx);
 
         {
             let name = self.output.name().replace("-", "_");
@@ -130,10 +255,16 @@ impl<E: WorkspaceElement> WorkspaceSet<E> {
             });
         }
 
-        self.switch_animation = Some(WorkspaceSwitchAnimation::new(target_idx));
+        self.switch_animation = Some(WorkspaceSwitchAnimation::new(target_idx, forward));
         self.workspaces[target_idx].focused().cloned()
     }
 
+    /// Move the sticky tiles of the workspace at `from_idx` to the workspace at `to_idx`.
+    fn move_sticky_tiles(&mut self, from_idx: usize, to_idx: usize) {
+        let tiles = self.workspaces[from_idx].take_sticky_tiles();
+        self.workspaces[to_idx].insert_sticky_tiles(tiles);
+    }
+
     /// Get the active workspace index of this [`WorkspaceSet`]
     ///
     /// If there's a switch animation going on, use the target index and not the currently active
@@ -146,6 +277,11 @@ impl<E: WorkspaceElement> WorkspaceSet<E> {
         }
     }
 
+    /// Get the previously active workspace index of this [`WorkspaceSet`], if any.
+    pub fn get_last_active_idx(&self) -> Option<usize> {
+        self.last_active_idx
+    }
+
     /// Get a reference to the active workspace.
     ///
     /// If there's a switch animation going on, use the target workspace and not the currently
@@ -250,52 +386,51 @@ impl<E: WorkspaceElement> WorkspaceSet<E> {
         let animation = self.switch_animation.as_ref().unwrap();
         let output_geo = self.output.geometry();
 
-        let (current_offset, target_offset) =
-            if animation.target_idx > self.active_idx.load(Ordering::SeqCst) {
-                // Focusing the next offset.
-                // For the active, how much should we *remove* from the current position
-                // For the target, how much should we add to the current position
-                match CONFIG.animation.workspace_switch.direction {
-                    WorkspaceSwitchAnimationDirection::Horizontal => {
-                        let offset =
-                            (animation.animation.value() * output_geo.size.w as f64).round() as i32;
-                        (
-                            Point::from(((-offset), 0)),
-                            Point::from(((-offset + output_geo.size.w), 0)),
-                        )
-                    }
-                    WorkspaceSwitchAnimationDirection::Vertical => {
-                        let offset =
-                            (animation.animation.value() * output_geo.size.h as f64).round() as i32;
-                        (
-                            Point::from((0, (-offset))),
-                            Point::from((0, (-offset + output_geo.size.h))),
-                        )
-                    }
+        let (current_offset, target_offset) = if animation.forward {
+            // Focusing the next offset.
+            // For the active, how much should we *remove* from the current position
+            // For the target, how much should we add to the current position
+            match CONFIG.animation.workspace_switch.direction {
+                WorkspaceSwitchAnimationDirection::Horizontal => {
+                    let offset =
+                        (animation.animation.value() * output_geo.size.w as f64).round() as i32;
+                    (
+                        Point::from(((-offset), 0)),
+                        Point::from(((-offset + output_geo.size.w), 0)),
+                    )
                 }
-            } else {
-                // Focusing a previous workspace
-                // For the active, how much should we add to tyhe current position
-                // For the target, how much should we remove from the current position.
-                match CONFIG.animation.workspace_switch.direction {
-                    WorkspaceSwitchAnimationDirection::Horizontal => {
-                        let offset =
-                            (animation.animation.value() * output_geo.size.w as f64).round() as i32;
-                        (
-                            Point::from((offset, 0)),
-                            Point::from((offset - output_geo.size.w, 0)),
-                        )
-                    }
-                    WorkspaceSwitchAnimationDirection::Vertical => {
-                        let offset =
-                            (animation.animation.value() * output_geo.size.h as f64).round() as i32;
-                        (
-                            Point::from((0, (offset))),
-                            Point::from((0, (offset - output_geo.size.h))),
-                        )
-                    }
+                WorkspaceSwitchAnimationDirection::Vertical => {
+                    let offset =
+                        (animation.animation.value() * output_geo.size.h as f64).round() as i32;
+                    (
+                        Point::from((0, (-offset))),
+                        Point::from((0, (-offset + output_geo.size.h))),
+                    )
+                }
+            }
+        } else {
+            // Focusing a previous workspace
+            // For the active, how much should we add to tyhe current position
+            // For the target, how much should we remove from the current position.
+            match CONFIG.animation.workspace_switch.direction {
+                WorkspaceSwitchAnimationDirection::Horizontal => {
+                    let offset =
+                        (animation.animation.value() * output_geo.size.w as f64).round() as i32;
+                    (
+                        Point::from((offset, 0)),
+                        Point::from((offset - output_geo.size.w, 0)),
+                    )
+                }
+                WorkspaceSwitchAnimationDirection::Vertical => {
+                    let offset =
+                        (animation.animation.value() * output_geo.size.h as f64).round() as i32;
+                    (
+                        Point::from((0, (offset))),
+                        Point::from((0, (offset - output_geo.size.h))),
+                    )
                 }
-            };
+            }
+        };
 
         self.active()
             .element_under(point + current_offset.to_f64())
@@ -352,52 +487,51 @@ impl<E: WorkspaceElement> WorkspaceSet<E> {
         }
 
         // Otherwise to computations
-        let (current_offset, target_offset) =
-            if animation.target_idx > self.active_idx.load(Ordering::SeqCst) {
-                // Focusing the next offset.
-                // For the active, how much should we *remove* from the current position
-                // For the target, how much should we add to the current position
-                match CONFIG.animation.workspace_switch.direction {
-                    WorkspaceSwitchAnimationDirection::Horizontal => {
-                        let offset =
-                            (animation.animation.value() * output_geo.size.w as f64).round() as i32;
-                        (
-                            Point::from(((-offset), 0)),
-                            Point::from(((-offset + output_geo.size.w), 0)),
-                        )
-                    }
-                    WorkspaceSwitchAnimationDirection::Vertical => {
-                        let offset =
-                            (animation.animation.value() * output_geo.size.h as f64).round() as i32;
-                        (
-                            Point::from((0, (-offset))),
-                            Point::from((0, (-offset + output_geo.size.h))),
-                        )
-                    }
+        let (current_offset, target_offset) = if animation.forward {
+            // Focusing the next offset.
+            // For the active, how much should we *remove* from the current position
+            // For the target, how much should we add to the current position
+            match CONFIG.animation.workspace_switch.direction {
+                WorkspaceSwitchAnimationDirection::Horizontal => {
+                    let offset =
+                        (animation.animation.value() * output_geo.size.w as f64).round() as i32;
+                    (
+                        Point::from(((-offset), 0)),
+                        Point::from(((-offset + output_geo.size.w), 0)),
+                    )
                 }
-            } else {
-                // Focusing a previous workspace
-                // For the active, how much should we add to tyhe current position
-                // For the target, how much should we remove from the current position.
-                match CONFIG.animation.workspace_switch.direction {
-                    WorkspaceSwitchAnimationDirection::Horizontal => {
-                        let offset =
-                            (animation.animation.value() * output_geo.size.w as f64).round() as i32;
-                        (
-                            Point::from((offset, 0)),
-                            Point::from((offset - output_geo.size.w, 0)),
-                        )
-                    }
-                    WorkspaceSwitchAnimationDirection::Vertical => {
-                        let offset =
-                            (animation.animation.value() * output_geo.size.h as f64).round() as i32;
-                        (
-                            Point::from((0, (offset))),
-                            Point::from((0, (offset - output_geo.size.h))),
-                        )
-                    }
+                WorkspaceSwitchAnimationDirection::Vertical => {
+                    let offset =
+                        (animation.animation.value() * output_geo.size.h as f64).round() as i32;
+                    (
+                        Point::from((0, (-offset))),
+                        Point::from((0, (-offset + output_geo.size.h))),
+                    )
+                }
+            }
+        } else {
+            // Focusing a previous workspace
+            // For the active, how much should we add to tyhe current position
+            // For the target, how much should we remove from the current position.
+            match CONFIG.animation.workspace_switch.direction {
+                WorkspaceSwitchAnimationDirection::Horizontal => {
+                    let offset =
+                        (animation.animation.value() * output_geo.size.w as f64).round() as i32;
+                    (
+                        Point::from((offset, 0)),
+                        Point::from((offset - output_geo.size.w, 0)),
+                    )
                 }
-            };
+                WorkspaceSwitchAnimationDirection::Vertical => {
+                    let offset =
+                        (animation.animation.value() * output_geo.size.h as f64).round() as i32;
+                    (
+                        Point::from((0, (offset))),
+                        Point::from((0, (offset - output_geo.size.h))),
+                    )
+                }
+            }
+        };
 
         elements.extend(active_elements.into_iter().filter_map(|element| {
             let relocate =
@@ -421,17 +555,182 @@ impl<E: WorkspaceElement> WorkspaceSet<E> {
     }
 }
 
+/// A snapshot of a [`Workspace`] layout parameters and tile proportions.
+#[derive(Debug, Clone)]
+pub struct LayoutPreset {
+    /// The layout with its parameters.
+    layout: WorkspaceLayout,
+    /// The cfacts of the tiles, associated with the app_id of their element.
+    cfacts: Vec<(String, f32)>,
+}
+
+/// The layouts of a [`Workspace`] and which one is active.
+///
+/// Used to carry the layout parameters of a workspace across output hotplugs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutState {
+    layouts: Vec<WorkspaceLayout>,
+    active_layout_idx: usize,
+}
+
+impl LayoutState {
+    /// Get the layouts a workspace using these ones should end up with when receiving `incoming`.
+    ///
+    /// The incoming layouts are only carried over if the workspace has nothing to lose, IE it
+    /// has no tiles, and if there are any.
+    fn merge(self, incoming: LayoutState, has_tiles: bool) -> LayoutState {
+        if has_tiles || incoming.layouts.is_empty() {
+            return self;
+        }
+
+        LayoutState {
+            active_layout_idx: incoming.active_layout_idx.min(incoming.layouts.len() - 1),
+            layouts: incoming.layouts,
+        }
+    }
+}
+
+/// A region of a [`Workspace`] to snap floating elements to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapRegion {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Maximize,
+    /// Center the element, keeping its size.
+    Center,
+}
+
+impl SnapRegion {
+    /// Get the geometry of this region inside `area`.
+    ///
+    /// `size` is the current size of the element to snap, border included, only used with
+    /// [`SnapRegion::Center`]
+    pub fn geometry(
+        self,
+        area: Rectangle<i32, Local>,
+        size: Size<i32, Local>,
+    ) -> Rectangle<i32, Local> {
+        let half_w = area.size.w / 2;
+        let half_h = area.size.h / 2;
+        let (loc, size): (Point<i32, Local>, Size<i32, Local>) = match self {
+            Self::Left => (area.loc, (half_w, area.size.h).into()),
+            Self::Right => (
+                area.loc + Point::from((half_w, 0)),
+                (area.size.w - half_w, area.size.h).into(),
+            ),
+            Self::Top => (area.loc, (area.size.w, half_h).into()),
+            Self::Bottom => (
+                area.loc + Point::from((0, half_h)),
+                (area.size.w, area.size.h - half_h).into(),
+            ),
+            Self::TopLeft => (area.loc, (half_w, half_h).into()),
+            Self::TopRight => (
+                area.loc + Point::from((half_w, 0)),
+                (area.size.w - half_w, half_h).into(),
+            ),
+            Self::BottomLeft => (
+                area.loc + Point::from((0, half_h)),
+                (half_w, area.size.h - half_h).into(),
+            ),
+            Self::BottomRight => (
+                area.loc + Point::from((half_w, half_h)),
+                (area.size.w - half_w, area.size.h - half_h).into(),
+            ),
+            Self::Maximize => (area.loc, area.size),
+            Self::Center => {
+                let loc = area.center() - size.downscale(2).to_point();
+                (loc, size)
+            }
+        };
+
+        Rectangle::from_loc_and_size(loc, size)
+    }
+}
+
+/// An edge of a [`Workspace`] to dock floating elements to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Edge {
+    /// Get the location of an element with `geometry` docked to this edge of `area`, `margin`
+    /// pixels away from it.
+    ///
+    /// For the non-corner edges, the element keeps its position on the other axis, clamped inside
+    /// `area`.
+    pub fn dock_location(
+        self,
+        area: Rectangle<i32, Local>,
+        geometry: Rectangle<i32, Local>,
+        margin: i32,
+    ) -> Point<i32, Local> {
+        let left = area.loc.x + margin;
+        let right = area.loc.x + area.size.w - geometry.size.w - margin;
+        let top = area.loc.y + margin;
+        let bottom = area.loc.y + area.size.h - geometry.size.h - margin;
+        let x = geometry.loc.x.min(right).max(left);
+        let y = geometry.loc.y.min(bottom).max(top);
+
+        match self {
+            Self::Left => (left, y),
+            Self::Right => (right, y),
+            Self::Top => (x, top),
+            Self::Bottom => (x, bottom),
+            Self::TopLeft => (left, top),
+            Self::TopRight => (right, top),
+            Self::BottomLeft => (left, bottom),
+            Self::BottomRight => (right, bottom),
+        }
+        .into()
+    }
+}
+
+/// An axis of a [`Workspace`] to maximize floating elements along.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+/// A direction to look for elements in a [`Workspace`], relative to the focused one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
 /// An active workspace switching animation
 pub struct WorkspaceSwitchAnimation {
     /// The underlying animation tweener to generate values
     pub animation: Animation,
     /// Which workspace are we going to focus.
     pub target_idx: usize,
+    /// Whether the target workspace comes after the active one.
+    ///
+    /// This is usually the case when the target index is greater than the active index, except
+    /// when wrapping around the workspaces.
+    pub forward: bool,
 }
 
 impl WorkspaceSwitchAnimation {
     /// Create a new [`WorkspaceSwitchAnimation`]
-    fn new(target_idx: usize) -> Self {
+    fn new(target_idx: usize, forward: bool) -> Self {
         // When going to the next workspace, the values describes the offset of the next workspace.
         // When going to the previous workspace, the values describe the offset of the current
         // workspace
@@ -447,6 +746,7 @@ impl WorkspaceSwitchAnimation {
         Self {
             animation,
             target_idx,
+            forward,
         }
     }
 }
@@ -471,6 +771,9 @@ pub struct Workspace<E: WorkspaceElement> {
     /// The index of the workspace.
     pub index: usize,
 
+    /// The name of the workspace, if any.
+    pub name: Option<String>,
+
     /// The tiles this workspace contains.
     ///
     /// These must all have valid [`WlSurface`]s (aka: being mapped), otherwise the workspace inner
@@ -490,6 +793,9 @@ pub struct Workspace<E: WorkspaceElement> {
     /// The active layout index.
     active_layout_idx: usize,
 
+    /// Saved layout presets, by name.
+    layout_presets: HashMap<String, LayoutPreset>,
+
     // Using an Arc is fine since workspaces are static to each output, so the ipc_path should
     // never be able to change.
     //
@@ -533,8 +839,14 @@ impl<E: WorkspaceElement> Workspace<E> {
         active: bool,
         ipc_path: String,
     ) -> Self {
+        let name = CONFIG.general.workspace_names.get(index).cloned();
+
         // IPC stuff.
-        let (ipc_workspace, channel) = IpcWorkspace::new(active, "bstack".into());
+        let (ipc_workspace, channel) = IpcWorkspace::new(
+            active,
+            CONFIG.general.layouts[0].to_string(),
+            name.clone().unwrap_or_default(),
+        );
         assert!(DBUS_CONNECTION
             .object_server()
             .at(ipc_path.as_str(), ipc_workspace)
@@ -553,6 +865,7 @@ impl<E: WorkspaceElement> Workspace<E> {
         Self {
             output,
             index,
+            name,
 
             tiles: vec![],
             // fullscreen: None,
@@ -560,6 +873,7 @@ impl<E: WorkspaceElement> Workspace<E> {
 
             layouts: CONFIG.general.layouts.clone(),
             active_layout_idx: 0,
+            layout_presets: HashMap::new(),
 
             ipc_path: ipc_path.as_str().into(),
             ipc_token,
@@ -567,22 +881,46 @@ impl<E: WorkspaceElement> Workspace<E> {
         }
     }
 
+    /// Set the name of this [`Workspace`].
+    pub fn set_name(&mut self, name: Option<String>) {
+        if self.name == name {
+            return;
+        }
+        self.name = name;
+
+        {
+            let ipc_path = self.ipc_path.clone();
+            let name = self.name.clone().unwrap_or_default();
+            spawn(async move {
+                let iface_ref = DBUS_CONNECTION
+                    .object_server()
+                    .inner()
+                    .interface::<_, IpcWorkspace>(ipc_path.as_ref())
+                    .await
+                    .unwrap();
+                let mut iface = iface_ref.get_mut().await;
+                iface.name = name;
+                iface
+                    .name_changed(iface_ref.signal_context())
+                    .await
+                    .unwrap();
+            });
+        }
+    }
+
     /// Get an iterator over this workspace's tiles.
     pub fn tiles(&self) -> impl Iterator<Item = &WorkspaceTile<E>> {
         self.tiles.iter()
     }
 
-    /// Refresh internal state of the [`Workspace`]
+    /// Remove the tiles whose element is dead, returning how many got removed.
     ///
-    /// Preferably call this before flushing clients.
-    #[profiling::function]
-    pub fn refresh(&mut self) {
-        let mut should_refresh_geometries = false;
-        // Invalidate current fullscreen if its dead
-
+    /// This is done in [`Self::refresh`], but you can call it to clean up right away.
+    pub fn reap_dead_tiles(&mut self) -> usize {
         // Clean dead/zombie tiles
         // Also ensure that we dont try to access out of bounds indexes, and sync up the IPC.
         let mut removed_ids = vec![];
+        let focused = self.focused().cloned();
         self.tiles.retain(|tile| {
             if !tile.element.alive() {
                 removed_ids.push(tile.element.uid());
@@ -591,33 +929,44 @@ impl<E: WorkspaceElement> Workspace<E> {
                 true
             }
         });
-        let new_len = self.tiles.len();
-        if !removed_ids.is_empty() {
-            should_refresh_geometries = true;
+        let removed = removed_ids.len();
+        if removed == 0 {
+            return 0;
+        }
 
-            {
-                let ipc_path = self.ipc_path.clone();
-                spawn(async move {
-                    let iface_ref = DBUS_CONNECTION
-                        .object_server()
-                        .inner()
-                        .interface::<_, IpcWorkspace>(ipc_path.as_ref())
-                        .await
-                        .unwrap();
-                    let mut iface = iface_ref.get_mut().await;
-                    iface.windows.retain(|uid| !removed_ids.contains(uid));
-                    iface
-                        .windows_changed(iface_ref.signal_context())
-                        .await
-                        .unwrap();
-                });
-            }
+        {
+            let ipc_path = self.ipc_path.clone();
+            spawn(async move {
+                let iface_ref = DBUS_CONNECTION
+                    .object_server()
+                    .inner()
+                    .interface::<_, IpcWorkspace>(ipc_path.as_ref())
+                    .await
+                    .unwrap();
+                let mut iface = iface_ref.get_mut().await;
+                iface.windows.retain(|uid| !removed_ids.contains(uid));
+                iface
+                    .windows_changed(iface_ref.signal_context())
+                    .await
+                    .unwrap();
+            });
         }
 
-        if should_refresh_geometries {
+        if !self.restore_focus(focused.as_ref()) {
+            let new_len = self.tiles.len();
             self.focused_tile_idx = self.focused_tile_idx.clamp(0, new_len.saturating_sub(1));
-            self.arrange_tiles();
         }
+        self.arrange_tiles();
+
+        removed
+    }
+
+    /// Refresh internal state of the [`Workspace`]
+    ///
+    /// Preferably call this before flushing clients.
+    #[profiling::function]
+    pub fn refresh(&mut self) {
+        self.reap_dead_tiles();
 
         // Refresh internal state of windows
         let output_geometry = self.output.geometry();
@@ -640,6 +989,15 @@ impl<E: WorkspaceElement> Workspace<E> {
         }
     }
 
+    /// Set whether this element is urgent, if this [`Workspace`] contains it.
+    ///
+    /// Urgent elements get their border drawn with `urgent_color` until they get focused.
+    pub fn set_element_urgent(&mut self, element: &E, urgent: bool) {
+        if let Some(tile) = self.tiles.iter_mut().find(|tile| tile.element == *element) {
+            tile.urgent = urgent;
+        }
+    }
+
     /// Find the element with this [`WlSurface`]
     pub fn find_element(&self, surface: &WlSurface) -> Option<&E> {
         self.tiles.iter().find_map(|tile| {
@@ -708,25 +1066,9 @@ impl<E: WorkspaceElement> Workspace<E> {
             .map(|tile| tile.geometry().to_global(&self.output))
     }
 
-    /// Insert a tile in this [`Workspace`]
-    ///
-    /// See [`Workspace::insert_element`]
-    pub fn insert_tile(&mut self, tile: WorkspaceTile<E>) {
-        let WorkspaceTile {
-            element,
-            border_config,
-            ..
-        } = tile;
-        self.insert_element(element, border_config);
-    }
-
-    /// Insert an element in this [`Workspace`]
-    ///
-    /// This function does additional configuration of the element before creating a tile for it,
-    /// mainly setting the bounds of the window, and notifying it of entering this
-    /// [`Workspace`] output.
+    /// Insert an element in this [`Workspace`], creating a new tile for it.
     ///
-    /// This doesn't reinsert the element if it's already inserted.
+    /// See [`Workspace::insert_tile`]
     pub fn insert_element(
         &mut self,
         window: E,
@@ -736,12 +1078,30 @@ impl<E: WorkspaceElement> Workspace<E> {
             return;
         }
 
+        self.insert_tile(WorkspaceTile::new(window, border_config));
+    }
+
+    /// Insert a tile in this [`Workspace`]
+    ///
+    /// This function does additional configuration of the element of the tile, mainly setting
+    /// the bounds of the window, and notifying it of entering this [`Workspace`] output.
+    ///
+    /// The tile is kept as-is, so its state (floating geometry, pinned, etc.) is preserved.
+    /// Floating tiles are always put at the end, the other ones follow the insert window strategy.
+    ///
+    /// This doesn't reinsert the element if it's already inserted.
+    pub fn insert_tile(&mut self, tile: WorkspaceTile<E>) {
+        if self.tiles.iter().any(|t| t.element == tile.element) {
+            return;
+        }
+
         // Output overlap + wl_surface scale and transform will be set when using self.refresh
-        window.set_bounds(Some(self.output.geometry().size.as_local()));
+        tile.element
+            .set_bounds(Some(self.output.geometry().size.as_local()));
 
         {
             let ipc_path = self.ipc_path.clone();
-            let uid = window.uid();
+            let uid = tile.element.uid();
             spawn(async move {
                 let iface_ref = DBUS_CONNECTION
                     .object_server()
@@ -758,8 +1118,12 @@ impl<E: WorkspaceElement> Workspace<E> {
             });
         }
 
-        let tile = WorkspaceTile::new(window, border_config);
+        let focused = self.focused().cloned();
         let new_idx = match CONFIG.general.insert_window_strategy {
+            _ if tile.floating => {
+                self.tiles.push(tile);
+                self.tiles.len() - 1
+            }
             InsertWindowStrategy::EndOfSlaveStack => {
                 self.tiles.push(tile);
                 self.tiles.len() - 1
@@ -783,6 +1147,8 @@ impl<E: WorkspaceElement> Workspace<E> {
 
         if CONFIG.general.focus_new_windows {
             self.focused_tile_idx = new_idx;
+        } else {
+            self.restore_focus(focused.as_ref());
         }
         self.arrange_tiles();
     }
@@ -795,13 +1161,16 @@ impl<E: WorkspaceElement> Workspace<E> {
             return None;
         };
 
+        let focused = self.focused().cloned();
         let tile = self.tiles.remove(idx);
         // "Un"-configure the window (for potentially inserting it on another workspace who knows)
         tile.element.output_leave(&self.output);
         tile.element.set_bounds(None);
-        self.focused_tile_idx = self
-            .focused_tile_idx
-            .clamp(0, self.tiles.len().saturating_sub(1));
+        if !self.restore_focus(focused.as_ref()) {
+            self.focused_tile_idx = self
+                .focused_tile_idx
+                .clamp(0, self.tiles.len().saturating_sub(1));
+        }
 
         {
             let ipc_path = self.ipc_path.clone();
@@ -826,6 +1195,43 @@ impl<E: WorkspaceElement> Workspace<E> {
         Some(tile)
     }
 
+    /// Keep the focus on this element after tiles got inserted or removed, returning whether it
+    /// did so.
+    ///
+    /// Since the focused tile is tracked by index, inserting or removing a tile before it would
+    /// otherwise move the focus to another element. This only does something if
+    /// `general.stable_focus_on_rearrange` is enabled and the element is still here.
+    fn restore_focus(&mut self, element: Option<&E>) -> bool {
+        if !CONFIG.general.stable_focus_on_rearrange {
+            return false;
+        }
+        let Some(idx) = element.and_then(|element| self.tiles.iter().position(|t| t == element))
+        else {
+            return false;
+        };
+
+        self.focused_tile_idx = idx;
+        {
+            let ipc_path = self.ipc_path.clone();
+            spawn(async move {
+                let iface_ref = DBUS_CONNECTION
+                    .object_server()
+                    .inner()
+                    .interface::<_, IpcWorkspace>(ipc_path.as_ref())
+                    .await
+                    .unwrap();
+                let mut iface = iface_ref.get_mut().await;
+                iface.focused_window_index = idx as u8;
+                iface
+                    .focused_window_changed(iface_ref.signal_context())
+                    .await
+                    .unwrap();
+            });
+        }
+
+        true
+    }
+
     /// Focus a given element, if this [`Workspace`] contains it.
     pub fn focus_element(&mut self, window: &E) {
         if let Some(idx) = self.tiles.iter().position(|w| w == window) {
@@ -925,6 +1331,70 @@ impl<E: WorkspaceElement> Workspace<E> {
         Some(tile.element())
     }
 
+    /// Focus the next element with this floating state, cycling back to the first one if needed.
+    ///
+    /// Elements with a different floating state are skipped.
+    pub fn focus_next_element_filtered(&mut self, floating: bool) -> Option<&E> {
+        let tiles_len = self.tiles.len();
+        let idx = (1..=tiles_len)
+            .map(|offset| (self.focused_tile_idx + offset) % tiles_len)
+            .find(|idx| self.tiles[*idx].floating == floating)?;
+        let element = self.tiles[idx].element.clone();
+        self.focus_element(&element);
+        self.focused()
+    }
+
+    /// Focus the previous element with this floating state, cycling all the way to the last
+    /// element if needed.
+    ///
+    /// Elements with a different floating state are skipped.
+    pub fn focus_previous_element_filtered(&mut self, floating: bool) -> Option<&E> {
+        let tiles_len = self.tiles.len();
+        let idx = (1..=tiles_len)
+            .map(|offset| (self.focused_tile_idx + tiles_len - offset) % tiles_len)
+            .find(|idx| self.tiles[*idx].floating == floating)?;
+        let element = self.tiles[idx].element.clone();
+        self.focus_element(&element);
+        self.focused()
+    }
+
+    /// Get the index of the closest tile in this direction from the focused tile, if any.
+    ///
+    /// Tiles are compared from center to center, so this works the same with every layout and
+    /// with floating tiles. Only the tiles matching `filter` are considered.
+    fn tile_idx_in_direction(
+        &self,
+        direction: Direction,
+        filter: impl Fn(&WorkspaceTile<E>) -> bool,
+    ) -> Option<usize> {
+        let focused_center = self.tiles.get(self.focused_tile_idx)?.geometry().center();
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter(|(idx, tile)| *idx != self.focused_tile_idx && filter(tile))
+            .filter_map(|(idx, tile)| {
+                let center = tile.geometry().center();
+                let (dx, dy) = (center.x - focused_center.x, center.y - focused_center.y);
+                let in_direction = match direction {
+                    Direction::Left => dx < 0,
+                    Direction::Right => dx > 0,
+                    Direction::Up => dy < 0,
+                    Direction::Down => dy > 0,
+                };
+                in_direction.then_some((idx, dx * dx + dy * dy))
+            })
+            .min_by_key(|(_, distance)| *distance)
+            .map(|(idx, _)| idx)
+    }
+
+    /// Focus the closest element in this direction from the focused one, if any.
+    pub fn focus_element_in_direction(&mut self, direction: Direction) -> Option<&E> {
+        let idx = self.tile_idx_in_direction(direction, |_| true)?;
+        let element = self.tiles[idx].element.clone();
+        self.focus_element(&element);
+        self.focused()
+    }
+
     /// Swap the two given elements.
     ///
     /// This will give the focus to b
@@ -940,11 +1410,48 @@ impl<E: WorkspaceElement> Workspace<E> {
         self.arrange_tiles();
     }
 
-    /// Swap the current element with the next element.
-    pub fn swap_with_next_element(&mut self) {
-        if self.tiles.len() < 2 {
-            return;
-        }
+    /// Swap the current element with the closest element in this direction from it, if any.
+    ///
+    /// The focus stays on the current element. Tiled elements only swap with tiled elements, and
+    /// floating elements with floating elements.
+    pub fn swap_with_element_in_direction(&mut self, direction: Direction) {
+        let Some(floating) = self
+            .tiles
+            .get(self.focused_tile_idx)
+            .map(|tile| tile.floating)
+        else {
+            return;
+        };
+        let Some(idx) = self.tile_idx_in_direction(direction, |tile| tile.floating == floating)
+        else {
+            return;
+        };
+        let focused = self.tiles[self.focused_tile_idx].element.clone();
+        let other = self.tiles[idx].element.clone();
+        self.swap_elements(&focused, &other);
+    }
+
+    /// Move this element to the master slot, putting the previous master element where this one
+    /// was.
+    ///
+    /// The focus follows the element. This does nothing for floating elements.
+    pub fn promote_element_to_master(&mut self, element: &E) {
+        let Some(idx) = self.tiles.iter().position(|tile| tile.element == *element) else {
+            return;
+        };
+        let Some(master_idx) = promote_to_master(&mut self.tiles, idx, |tile| tile.floating) else {
+            return;
+        };
+
+        self.focused_tile_idx = master_idx;
+        self.arrange_tiles();
+    }
+
+    /// Swap the current element with the next element.
+    pub fn swap_with_next_element(&mut self) {
+        if self.tiles.len() < 2 {
+            return;
+        }
 
         let tiles_len = self.tiles.len();
         let last_focused_idx = self.focused_tile_idx;
@@ -980,6 +1487,313 @@ impl<E: WorkspaceElement> Workspace<E> {
         self.arrange_tiles();
     }
 
+    /// Float or tile back a given element, if this [`Workspace`] contains it.
+    ///
+    /// Floating tiles are moved to the end of the tiles list, remembering their previous index so
+    /// that tiling them back restores their original slot. If that slot doesn't exist anymore,
+    /// the tile gets put at the end.
+    ///
+    /// If `floating_defaults` has a size for the element app_id, the floated tile gets that size,
+    /// centered in the workspace. Otherwise, it keeps the geometry it had.
+    pub fn set_element_floating(&mut self, element: &E, floating: bool) {
+        let usable_geo = self.usable_geometry();
+        let Some(idx) = self.tiles.iter().position(|tile| tile.element == *element) else {
+            return;
+        };
+        if self.tiles[idx].floating == floating {
+            return;
+        }
+
+        let focused = self.focused().cloned();
+        let mut tile = self.tiles.remove(idx);
+        tile.floating = floating;
+        // Sticky tiles must be floating.
+        tile.sticky &= floating;
+        if !floating {
+            // The layout decides the geometry now, nothing to restore anymore.
+            tile.pre_hmaximize = None;
+            tile.pre_vmaximize = None;
+        }
+        if floating {
+            tile.last_tiled_index = Some(idx);
+            if let Some(size) = CONFIG.floating_defaults.size_for(&tile.element.app_id()) {
+                let size = Size::from(size);
+                let loc = usable_geo.center() - size.downscale(2).to_point();
+                tile.set_geometry_excluding_border(Rectangle::from_loc_and_size(loc, size));
+            }
+            self.tiles.push(tile);
+        } else {
+            let new_idx = tile
+                .last_tiled_index
+                .take()
+                .map_or(self.tiles.len(), |idx| idx.min(self.tiles.len()));
+            self.tiles.insert(new_idx, tile);
+        }
+
+        if let Some(idx) =
+            focused.and_then(|focused| self.tiles.iter().position(|tile| *tile == focused))
+        {
+            self.focused_tile_idx = idx;
+        }
+        self.arrange_tiles();
+    }
+
+    /// Toggle the floating state of a given element, if this [`Workspace`] contains it.
+    ///
+    /// See [`Workspace::set_element_floating`]
+    pub fn toggle_element_floating(&mut self, element: &E) {
+        let Some(floating) = self
+            .tiles
+            .iter()
+            .find(|tile| tile.element == *element)
+            .map(|tile| tile.floating)
+        else {
+            return;
+        };
+        self.set_element_floating(element, !floating);
+    }
+
+    /// Set the floating state of every element in this [`Workspace`] at once.
+    ///
+    /// Pinned elements are skipped, see [`Workspace::set_element_floating`]
+    pub fn set_all_floating(&mut self, floating: bool) {
+        let mut elements = self
+            .tiles
+            .iter()
+            .filter(|tile| tile.floating != floating && !tile.pinned)
+            .map(|tile| (tile.element.clone(), tile.last_tiled_index))
+            .collect::<Vec<_>>();
+        if floating {
+            // Start from the end so that the tiled indices of the remaining tiles don't shift.
+            elements.reverse();
+        } else {
+            // Reinsert in order so that each element lands back at its tiled index.
+            elements.sort_by_key(|(_, last_tiled_index)| *last_tiled_index);
+        }
+
+        for (element, _) in elements {
+            self.set_element_floating(&element, floating);
+        }
+    }
+
+    /// Set the sticky state of a given element, if this [`Workspace`] contains it.
+    ///
+    /// Sticky elements are floated, and get moved along to the new active workspace when the
+    /// [`WorkspaceSet`] switches workspaces, so that they appear on every workspace of their
+    /// output. Unsticking an element keeps it floating on the workspace it's currently on.
+    ///
+    /// Sticky elements keep their focus when moving, and closing one closes the window for good,
+    /// since it only lives in one workspace at a time.
+    pub fn set_element_sticky(&mut self, element: &E, sticky: bool) {
+        let Some(tile) = self.tiles.iter_mut().find(|tile| tile.element == *element) else {
+            return;
+        };
+        tile.sticky = sticky;
+        if sticky {
+            self.set_element_floating(element, true);
+        }
+    }
+
+    /// Set the pinned state of a given element, if this [`Workspace`] contains it.
+    ///
+    /// Pinned elements opt out of the operations acting on every element of the workspace, like
+    /// [`Workspace::set_all_floating`] and [`Workspace::arrange_floating_tiles`]. This is unrelated
+    /// to sticky elements, pinned elements don't follow the active workspace.
+    pub fn set_element_pinned(&mut self, element: &E, pinned: bool) {
+        if let Some(tile) = self.tiles.iter_mut().find(|tile| tile.element == *element) {
+            tile.pinned = pinned;
+        }
+    }
+
+    /// Toggle the pinned state of a given element, if this [`Workspace`] contains it.
+    ///
+    /// See [`Workspace::set_element_pinned`]
+    pub fn toggle_element_pinned(&mut self, element: &E) {
+        if let Some(tile) = self.tiles.iter_mut().find(|tile| tile.element == *element) {
+            tile.pinned = !tile.pinned;
+        }
+    }
+
+    /// Set the runtime border overrides of a given element, if this [`Workspace`] contains it.
+    pub fn set_element_border_overrides(&mut self, element: &E, overrides: BorderOverrides) {
+        let Some(tile) = self.tiles.iter_mut().find(|tile| tile.element == *element) else {
+            return;
+        };
+        tile.border_overrides = overrides;
+        // The thickness can change, affecting the tile geometry.
+        self.arrange_tiles();
+    }
+
+    /// Toggle the sticky state of a given element, if this [`Workspace`] contains it.
+    ///
+    /// See [`Workspace::set_element_sticky`]
+    pub fn toggle_element_sticky(&mut self, element: &E) {
+        let Some(sticky) = self
+            .tiles
+            .iter()
+            .find(|tile| tile.element == *element)
+            .map(|tile| tile.sticky)
+        else {
+            return;
+        };
+        self.set_element_sticky(element, !sticky);
+    }
+
+    /// Take out the sticky tiles of this [`Workspace`] to move them to another workspace of the
+    /// same output.
+    fn take_sticky_tiles(&mut self) -> Vec<WorkspaceTile<E>> {
+        if !self.tiles.iter().any(|tile| tile.sticky) {
+            return vec![];
+        }
+
+        let focused = self.focused().cloned();
+        let (sticky, tiles) = std::mem::take(&mut self.tiles)
+            .into_iter()
+            .partition::<Vec<_>, _>(|tile| tile.sticky);
+        self.tiles = tiles;
+        if !self.restore_focus(focused.as_ref()) {
+            self.focused_tile_idx = self
+                .focused_tile_idx
+                .clamp(0, self.tiles.len().saturating_sub(1));
+        }
+
+        {
+            let ipc_path = self.ipc_path.clone();
+            let removed_ids = sticky
+                .iter()
+                .map(|tile| tile.element.uid())
+                .collect::<Vec<_>>();
+            spawn(async move {
+                let iface_ref = DBUS_CONNECTION
+                    .object_server()
+                    .inner()
+                    .interface::<_, IpcWorkspace>(ipc_path.as_ref())
+                    .await
+                    .unwrap();
+                let mut iface = iface_ref.get_mut().await;
+                iface.windows.retain(|uid| !removed_ids.contains(uid));
+                iface
+                    .windows_changed(iface_ref.signal_context())
+                    .await
+                    .unwrap();
+            });
+        }
+
+        self.arrange_tiles();
+        sticky
+    }
+
+    /// Take out every tile of this [`Workspace`], to move them to another output.
+    ///
+    /// See [`Workspace::merge_tiles`]
+    pub fn take_tiles(&mut self) -> Vec<WorkspaceTile<E>> {
+        let tiles = std::mem::take(&mut self.tiles);
+        for tile in &tiles {
+            tile.element.output_leave(&self.output);
+        }
+        self.focused_tile_idx = 0;
+
+        {
+            let ipc_path = self.ipc_path.clone();
+            spawn(async move {
+                let iface_ref = DBUS_CONNECTION
+                    .object_server()
+                    .inner()
+                    .interface::<_, IpcWorkspace>(ipc_path.as_ref())
+                    .await
+                    .unwrap();
+                let mut iface = iface_ref.get_mut().await;
+                iface.windows.clear();
+                iface
+                    .windows_changed(iface_ref.signal_context())
+                    .await
+                    .unwrap();
+            });
+        }
+
+        tiles
+    }
+
+    /// Merge tiles taken out of a workspace of another output in this [`Workspace`].
+    ///
+    /// The layouts of the other workspace are carried over if this one had nothing to lose.
+    /// Unlike [`Workspace::insert_tile`], this doesn't touch the focus nor the insert strategy.
+    pub fn merge_tiles(&mut self, layout_state: LayoutState, tiles: Vec<WorkspaceTile<E>>) {
+        if tiles.is_empty() {
+            return;
+        }
+
+        // Little optimizaztion, to avoid recalculating window geometries each time
+        //
+        // Due to how we manage windows, a window can't be in two workspaces at a time, let
+        // alone from different outputs
+        self.restore_layout_state(layout_state);
+
+        {
+            let ipc_path = self.ipc_path.clone();
+            let added_ids = tiles
+                .iter()
+                .map(|tile| tile.element.uid())
+                .collect::<Vec<_>>();
+            spawn(async move {
+                let iface_ref = DBUS_CONNECTION
+                    .object_server()
+                    .inner()
+                    .interface::<_, IpcWorkspace>(ipc_path.as_ref())
+                    .await
+                    .unwrap();
+                let mut iface = iface_ref.get_mut().await;
+                iface.windows.extend(added_ids);
+                iface
+                    .windows_changed(iface_ref.signal_context())
+                    .await
+                    .unwrap();
+            });
+        }
+
+        let bounds = self.output.geometry().size.as_local();
+        for tile in &tiles {
+            tile.element.set_bounds(Some(bounds));
+        }
+        self.tiles.extend(tiles);
+        self.arrange_tiles();
+    }
+
+    /// Insert sticky tiles taken out of another workspace of the same output.
+    ///
+    /// Unlike [`Workspace::insert_tile`], this doesn't touch the focus nor the insert strategy.
+    fn insert_sticky_tiles(&mut self, tiles: Vec<WorkspaceTile<E>>) {
+        if tiles.is_empty() {
+            return;
+        }
+
+        {
+            let ipc_path = self.ipc_path.clone();
+            let added_ids = tiles
+                .iter()
+                .map(|tile| tile.element.uid())
+                .collect::<Vec<_>>();
+            spawn(async move {
+                let iface_ref = DBUS_CONNECTION
+                    .object_server()
+                    .inner()
+                    .interface::<_, IpcWorkspace>(ipc_path.as_ref())
+                    .await
+                    .unwrap();
+                let mut iface = iface_ref.get_mut().await;
+                iface.windows.extend(added_ids);
+                iface
+                    .windows_changed(iface_ref.signal_context())
+                    .await
+                    .unwrap();
+            });
+        }
+
+        // Sticky tiles are always floating, so they go at the end.
+        self.tiles.extend(tiles);
+        self.arrange_tiles();
+    }
+
     /// Refresh the geometries of the tiles contained in this [`Workspace`].
     ///
     /// This ensures geometry for maximized and tiled elements.
@@ -990,30 +1804,361 @@ impl<E: WorkspaceElement> Workspace<E> {
         }
 
         let layout = self.get_active_layout();
-        let (maximized, tiled) = self
+        let inner_gaps = CONFIG.general.inner_gaps;
+        let maximized_geo = self.usable_geometry();
+        let non_exclusive_zone = layer_map_for_output(&self.output)
+            .non_exclusive_zone()
+            .as_local();
+
+        for tile in &mut self.tiles {
+            tile.border_hidden = false;
+        }
+
+        let (maximized, mut tiled) = self
             .tiles
             .iter_mut()
+            .filter(|tile| tile.element.maximized() || !tile.floating)
             .partition::<Vec<_>, _>(|tile| tile.element.maximized());
+        for tile in maximized {
+            tile.set_geometry(maximized_geo)
+        }
 
-        let inner_gaps = CONFIG.general.inner_gaps;
-        let outer_gaps = CONFIG.general.outer_gaps;
+        if tiled.is_empty() {
+            return;
+        }
 
-        let usable_geo = layer_map_for_output(&self.output)
+        let tiled_len = tiled.len();
+        if tiled_len == 1 && CONFIG.general.smart_borders {
+            tiled[0].border_hidden = true;
+        }
+        let tiled_geo = if tiled_len == 1 && CONFIG.general.smart_gaps {
+            non_exclusive_zone
+        } else {
+            maximized_geo
+        };
+        layout.arrange_tiles(tiled.into_iter(), tiled_len, tiled_geo, inner_gaps);
+    }
+
+    /// Whether the tile at this index is hidden by the [`WorkspaceLayout::Monocle`] layout.
+    ///
+    /// Only the focused tiled tile is visible, or the first one if a floating tile has focus.
+    fn is_tile_hidden(&self, idx: usize) -> bool {
+        if self.get_active_layout() != WorkspaceLayout::Monocle || self.tiles[idx].floating {
+            return false;
+        }
+
+        let visible_idx = if self
+            .tiles
+            .get(self.focused_tile_idx)
+            .is_some_and(|tile| !tile.floating)
+        {
+            self.focused_tile_idx
+        } else {
+            self.tiles.iter().position(|tile| !tile.floating).unwrap()
+        };
+        idx != visible_idx
+    }
+
+    /// Get the usable geometry of this [`Workspace`].
+    ///
+    /// This is the non-exclusive zone of the output (not taken by layer shells), with the outer
+    /// gaps applied.
+    pub fn usable_geometry(&self) -> Rectangle<i32, Local> {
+        let outer_gaps = CONFIG.general.outer_gaps;
+        let mut usable_geo = layer_map_for_output(&self.output)
             .non_exclusive_zone()
             .as_local();
-        let mut maximized_geo = usable_geo;
-        maximized_geo.size -= (2 * outer_gaps, 2 * outer_gaps).into();
-        maximized_geo.loc += (outer_gaps, outer_gaps).into();
-        for tile in maximized {
-            tile.set_geometry(maximized_geo)
+        usable_geo.size -= (2 * outer_gaps, 2 * outer_gaps).into();
+        usable_geo.loc += (outer_gaps, outer_gaps).into();
+        usable_geo
+    }
+
+    /// Snap a floating element to a given region of this [`Workspace`].
+    ///
+    /// This works with floating elements, and any element when the active layout is
+    /// [`WorkspaceLayout::Floating`], since the layout doesn't position them.
+    pub fn snap_element(&mut self, element: &E, region: SnapRegion) {
+        let usable_geo = self.usable_geometry();
+        let floating_layout = matches!(self.get_active_layout(), WorkspaceLayout::Floating);
+        let Some(tile) = self.tiles.iter_mut().find(|tile| tile.element == *element) else {
+            return;
+        };
+        if !tile.floating && !floating_layout {
+            return;
         }
 
-        if tiled.is_empty() {
+        let new_geo = region.geometry(usable_geo, tile.geometry_with_border().size);
+        tile.set_geometry(new_geo);
+    }
+
+    /// Toggle maximizing this element along an axis of the workspace usable area.
+    ///
+    /// Toggling it again restores the location and size the element had on that axis. This only
+    /// works with floating elements.
+    pub fn toggle_element_axis_maximized(&mut self, element: &E, axis: Axis) {
+        let usable_geo = self.usable_geometry();
+        let Some(tile) = self.tiles.iter_mut().find(|tile| tile.element == *element) else {
+            return;
+        };
+        if !tile.floating {
+            return;
+        }
+
+        let mut new_geo = tile.geometry_with_border();
+        match axis {
+            Axis::Horizontal => {
+                if let Some((x, w)) = tile.pre_hmaximize.take() {
+                    new_geo.loc.x = x;
+                    new_geo.size.w = w;
+                } else {
+                    tile.pre_hmaximize = Some((new_geo.loc.x, new_geo.size.w));
+                    new_geo.loc.x = usable_geo.loc.x;
+                    new_geo.size.w = usable_geo.size.w;
+                }
+            }
+            Axis::Vertical => {
+                if let Some((y, h)) = tile.pre_vmaximize.take() {
+                    new_geo.loc.y = y;
+                    new_geo.size.h = h;
+                } else {
+                    tile.pre_vmaximize = Some((new_geo.loc.y, new_geo.size.h));
+                    new_geo.loc.y = usable_geo.loc.y;
+                    new_geo.size.h = usable_geo.size.h;
+                }
+            }
+        }
+        tile.set_geometry(new_geo);
+    }
+
+    /// Resize this element to the largest area of the workspace not covered by other tiles.
+    ///
+    /// This only works with floating elements.
+    pub fn grow_element_to_empty_space(&mut self, element: &E) {
+        let usable_geo = self.usable_geometry();
+        if !self
+            .tiles
+            .iter()
+            .any(|tile| tile.element == *element && tile.floating)
+        {
+            return;
+        }
+
+        let obstacles = self
+            .tiles
+            .iter()
+            .filter(|tile| tile.element != *element)
+            .map(WorkspaceTile::geometry_with_border)
+            .collect::<Vec<_>>();
+
+        // The largest empty rectangle always has its edges on the usable area or obstacle edges.
+        let (left, right) = (usable_geo.loc.x, usable_geo.loc.x + usable_geo.size.w);
+        let (top, bottom) = (usable_geo.loc.y, usable_geo.loc.y + usable_geo.size.h);
+        let mut xs = vec![left, right];
+        let mut ys = vec![top, bottom];
+        for geo in &obstacles {
+            xs.extend([geo.loc.x, geo.loc.x + geo.size.w]);
+            ys.extend([geo.loc.y, geo.loc.y + geo.size.h]);
+        }
+        xs.retain(|x| (left..=right).contains(x));
+        ys.retain(|y| (top..=bottom).contains(y));
+        xs.sort_unstable();
+        xs.dedup();
+        ys.sort_unstable();
+        ys.dedup();
+
+        let mut largest: Option<(i64, Rectangle<i32, Local>)> = None;
+        for (i, x1) in xs.iter().enumerate() {
+            for x2 in &xs[i + 1..] {
+                for (j, y1) in ys.iter().enumerate() {
+                    for y2 in &ys[j + 1..] {
+                        let rect = Rectangle::from_loc_and_size((*x1, *y1), (x2 - x1, y2 - y1));
+                        if obstacles.iter().any(|geo| geo.overlaps(rect)) {
+                            // Growing further down would overlap too.
+                            break;
+                        }
+                        let area = rect.size.w as i64 * rect.size.h as i64;
+                        if largest.map_or(true, |(largest_area, _)| area > largest_area) {
+                            largest = Some((area, rect));
+                        }
+                    }
+                }
+            }
+        }
+
+        let Some((_, new_geo)) = largest else {
+            return;
+        };
+        let tile = self
+            .tiles
+            .iter_mut()
+            .find(|tile| tile.element == *element)
+            .unwrap();
+        tile.set_geometry(new_geo);
+    }
+
+    /// Arrange the floating tiles of this [`Workspace`] in a grid over the usable area, without
+    /// tiling them. Pinned tiles are left in place.
+    ///
+    /// Returns the number of arranged tiles.
+    pub fn arrange_floating_tiles(&mut self) -> usize {
+        let usable_geo = self.usable_geometry();
+        let inner_gaps = CONFIG.general.inner_gaps;
+        let floating = self
+            .tiles
+            .iter_mut()
+            .filter(|tile| {
+                tile.floating
+                    && !tile.pinned
+                    && !tile.element.maximized()
+                    && !tile.element.fullscreen()
+            })
+            .collect::<Vec<_>>();
+        let count = floating.len();
+        for (tile, geo) in floating
+            .into_iter()
+            .zip(layout::grid_cells(count, usable_geo, inner_gaps))
+        {
+            tile.set_geometry(geo);
+        }
+
+        count
+    }
+
+    /// Dock this element flush to an edge of the workspace, keeping its size.
+    ///
+    /// This only works with floating elements.
+    pub fn dock_element(&mut self, element: &E, edge: Edge, margin: i32) {
+        let usable_geo = self.usable_geometry();
+        let Some(tile) = self.tiles.iter_mut().find(|tile| tile.element == *element) else {
+            return;
+        };
+        if !tile.floating {
+            return;
+        }
+
+        let mut new_geo = tile.geometry_with_border();
+        new_geo.loc = edge.dock_location(usable_geo, new_geo, margin);
+        tile.set_geometry(new_geo);
+    }
+
+    /// Resize this element to a fraction of the workspace usable area, keeping its center.
+    ///
+    /// The fractions are clamped to `0.05..=1.0`. This only works with floating elements.
+    pub fn resize_element_relative(&mut self, element: &E, width_frac: f64, height_frac: f64) {
+        let usable_geo = self.usable_geometry();
+        let Some(tile) = self.tiles.iter_mut().find(|tile| tile.element == *element) else {
+            return;
+        };
+        if !tile.floating {
             return;
         }
 
+        let width = (usable_geo.size.w as f64 * width_frac.clamp(0.05, 1.0)).round() as i32;
+        let height = (usable_geo.size.h as f64 * height_frac.clamp(0.05, 1.0)).round() as i32;
+        let size = Size::from((width, height));
+        let loc = tile.geometry().center() - size.downscale(2).to_point();
+        tile.set_geometry(Rectangle::from_loc_and_size(loc, size));
+    }
+
+    /// Grow a tiled element toward one of its edges by `amount` pixels, shrinking it if `amount`
+    /// is negative.
+    ///
+    /// Depending on the edge, this either moves the master/stack boundary (changing the master
+    /// width factor) or the boundary between the element and its neighbours (changing its
+    /// cfact). The supported edges depend on the active layout:
+    /// - `Tile`: Right for master elements, Left for stack elements, Top and Bottom for both.
+    /// - `BottomStack`: Bottom for master elements, Top for stack elements, Left and Right for
+    ///   both.
+    /// - `CenteredMaster`: Left and Right for master elements, the edge facing the master for stack
+    ///   elements, Top and Bottom for both.
+    ///
+    /// Other edges, floating elements and the `Floating` layout are ignored.
+    pub fn resize_element_directional(&mut self, element: &E, edge: Edge, amount: i32) {
+        enum Boundary {
+            // The master/stack boundary, with the sign to apply to the mwfact delta.
+            Master(f32),
+            // The boundary between the element and its neighbours of the same stack.
+            Stack,
+        }
+
+        let usable_geo = self.usable_geometry();
+        let layout = self.get_active_layout();
+        let tiled = self
+            .tiles
+            .iter()
+            .filter(|tile| !tile.floating && !tile.element.maximized())
+            .collect::<Vec<_>>();
         let tiled_len = tiled.len();
-        layout.arrange_tiles(tiled.into_iter(), tiled_len, maximized_geo, inner_gaps);
+        let Some((tiled_idx, tile_geo)) = tiled
+            .iter()
+            .position(|tile| tile.element == *element)
+            .map(|idx| (idx, tiled[idx].geometry()))
+        else {
+            return;
+        };
+
+        let (nmaster, boundary) = match layout {
+            WorkspaceLayout::Tile { nmaster, .. } => {
+                let boundary = match (edge, tiled_idx < nmaster) {
+                    (Edge::Right, true) => Some(Boundary::Master(1.0)),
+                    (Edge::Left, false) => Some(Boundary::Master(-1.0)),
+                    (Edge::Top | Edge::Bottom, _) => Some(Boundary::Stack),
+                    _ => None,
+                };
+                (nmaster, boundary)
+            }
+            WorkspaceLayout::BottomStack { nmaster, .. } => {
+                let boundary = match (edge, tiled_idx < nmaster) {
+                    (Edge::Bottom, true) => Some(Boundary::Master(1.0)),
+                    (Edge::Top, false) => Some(Boundary::Master(-1.0)),
+                    (Edge::Left | Edge::Right, _) => Some(Boundary::Stack),
+                    _ => None,
+                };
+                (nmaster, boundary)
+            }
+            WorkspaceLayout::CenteredMaster { nmaster, .. } => {
+                // Stack elements on the left of the master face it with their right edge, and
+                // the ones on the right with their left edge.
+                let on_left = tile_geo.center().x < usable_geo.center().x;
+                let boundary = match (edge, tiled_idx < nmaster) {
+                    (Edge::Left | Edge::Right, true) => Some(Boundary::Master(1.0)),
+                    (Edge::Right, false) if on_left => Some(Boundary::Master(-1.0)),
+                    (Edge::Left, false) if !on_left => Some(Boundary::Master(-1.0)),
+                    (Edge::Top | Edge::Bottom, _) => Some(Boundary::Stack),
+                    _ => None,
+                };
+                (nmaster, boundary)
+            }
+            WorkspaceLayout::Monocle | WorkspaceLayout::Grid | WorkspaceLayout::Floating => return,
+        };
+
+        match boundary {
+            // There's no master/stack boundary without a stack.
+            Some(Boundary::Master(sign)) if tiled_len > nmaster => {
+                let size = if matches!(layout, WorkspaceLayout::BottomStack { .. }) {
+                    usable_geo.size.h
+                } else {
+                    usable_geo.size.w
+                };
+                self.change_mwfact(sign * amount as f32 / size.max(1) as f32);
+            }
+            Some(Boundary::Stack) => {
+                let size = if matches!(layout, WorkspaceLayout::BottomStack { .. }) {
+                    tile_geo.size.w
+                } else {
+                    tile_geo.size.h
+                };
+                let Some(tile) = self.tiles.iter_mut().find(|tile| tile.element == *element) else {
+                    return;
+                };
+                // Growing the element by `amount` pixels roughly means scaling its cfact by the
+                // same ratio, since its neighbours keep their own cfacts.
+                let delta = tile.cfact * amount as f32 / size.max(1) as f32;
+                tile.cfact = (tile.cfact + delta).max(0.1);
+                self.arrange_tiles();
+            }
+            _ => (),
+        }
     }
 
     /// Get the active layout that arranges the tiles
@@ -1065,6 +2210,8 @@ impl<E: WorkspaceElement> Workspace<E> {
             None => layouts_len - 1,
         };
 
+        self.active_layout_idx = new_active_idx;
+
         {
             let layout = self.layouts[self.active_layout_idx].to_string();
             let ipc_path = self.ipc_path.clone();
@@ -1084,7 +2231,113 @@ impl<E: WorkspaceElement> Workspace<E> {
             });
         }
 
-        self.active_layout_idx = new_active_idx;
+        self.arrange_tiles();
+    }
+
+    /// Get the layouts of this [`Workspace`], with their parameters and the active one.
+    pub fn layout_state(&self) -> LayoutState {
+        LayoutState {
+            layouts: self.layouts.clone(),
+            active_layout_idx: self.active_layout_idx,
+        }
+    }
+
+    /// Restore layouts taken from a [`Workspace`] with [`Workspace::layout_state`].
+    ///
+    /// This does nothing if this [`Workspace`] has tiles, since it would lose its own layouts.
+    pub fn restore_layout_state(&mut self, state: LayoutState) {
+        let current = self.layout_state();
+        let state = current.clone().merge(state, !self.tiles.is_empty());
+        if state == current {
+            return;
+        }
+        self.active_layout_idx = state.active_layout_idx;
+        self.layouts = state.layouts;
+
+        {
+            let ipc_path = self.ipc_path.clone();
+            let layout = self.layouts[self.active_layout_idx].to_string();
+            spawn(async move {
+                let iface_ref = DBUS_CONNECTION
+                    .object_server()
+                    .inner()
+                    .interface::<_, IpcWorkspace>(ipc_path.as_ref())
+                    .await
+                    .unwrap();
+                let mut iface = iface_ref.get_mut().await;
+                iface.active_layout = layout;
+                iface
+                    .active_layout_changed(iface_ref.signal_context())
+                    .await
+                    .unwrap();
+            });
+        }
+
+        self.arrange_tiles();
+    }
+
+    /// Save the active layout parameters and tile proportions in a preset with this name.
+    ///
+    /// This overrides any preset with the same name.
+    pub fn save_layout_preset(&mut self, name: String) {
+        let preset = LayoutPreset {
+            layout: self.get_active_layout(),
+            cfacts: self
+                .tiles
+                .iter()
+                .map(|tile| (tile.element.app_id(), tile.cfact))
+                .collect(),
+        };
+        self.layout_presets.insert(name, preset);
+    }
+
+    /// Load the layout preset with this name, if it exists.
+    ///
+    /// Tile proportions are applied by matching app_ids, in order, so that a preset reapplies to a
+    /// similar set of windows.
+    pub fn load_layout_preset(&mut self, name: &str) {
+        let Some(preset) = self.layout_presets.get(name) else {
+            warn!(?name, "No layout preset with this name!");
+            return;
+        };
+
+        let Some(layout_idx) = self.layouts.iter().position(|layout| {
+            std::mem::discriminant(layout) == std::mem::discriminant(&preset.layout)
+        }) else {
+            warn!(?name, "Layout preset uses a layout that's not available!");
+            return;
+        };
+        self.layouts[layout_idx] = preset.layout;
+        self.active_layout_idx = layout_idx;
+
+        let mut cfacts = preset.cfacts.clone();
+        for tile in &mut self.tiles {
+            let app_id = tile.element.app_id();
+            if let Some(idx) = cfacts.iter().position(|(id, _)| *id == app_id) {
+                let (_, cfact) = cfacts.remove(idx);
+                tile.cfact = cfact;
+            }
+        }
+
+        {
+            let ipc_path = self.ipc_path.clone();
+            let layout = self.layouts[self.active_layout_idx].to_string();
+            spawn(async move {
+                let iface_ref = DBUS_CONNECTION
+                    .object_server()
+                    .inner()
+                    .interface::<_, IpcWorkspace>(ipc_path.as_ref())
+                    .await
+                    .unwrap();
+                let mut iface = iface_ref.get_mut().await;
+                iface.active_layout = layout;
+                iface
+                    .active_layout_changed(iface_ref.signal_context())
+                    .await
+                    .unwrap();
+            });
+        }
+
         self.arrange_tiles();
     }
 
@@ -1129,6 +2382,35 @@ impl<E: WorkspaceElement> Workspace<E> {
         self.arrange_tiles();
     }
 
+    /// Set the mwfact and nmaster of the active [`WorkspaceLayout`] at once.
+    ///
+    /// Values that are [`None`] are left untouched. The same clamping as [`Self::change_mwfact`]
+    /// and [`Self::change_nmaster`] applies.
+    pub fn set_layout_params(&mut self, mwfact: Option<f32>, nmaster: Option<usize>) {
+        let active_layout = &mut self.layouts[self.active_layout_idx];
+        if let WorkspaceLayout::Tile {
+            master_width_factor,
+            nmaster: layout_nmaster,
+        }
+        | WorkspaceLayout::BottomStack {
+            master_width_factor,
+            nmaster: layout_nmaster,
+        }
+        | WorkspaceLayout::CenteredMaster {
+            master_width_factor,
+            nmaster: layout_nmaster,
+        } = active_layout
+        {
+            if let Some(mwfact) = mwfact {
+                *master_width_factor = mwfact.clamp(0.0, 0.95);
+            }
+            if let Some(nmaster) = nmaster {
+                *layout_nmaster = nmaster.max(1);
+            }
+        }
+        self.arrange_tiles();
+    }
+
     /// Get the element under the pointer in this workspace.
     #[profiling::function]
     pub fn element_under(&self, point: Point<f64, Global>) -> Option<(&E, Point<i32, Global>)> {
@@ -1146,8 +2428,17 @@ impl<E: WorkspaceElement> Workspace<E> {
             }
         }
 
-        self.tiles
+        // Floating tiles are rendered above tiled ones, so check them first.
+        let (floating, tiled) = self
+            .tiles
             .iter()
+            .enumerate()
+            .filter(|(idx, _)| !self.is_tile_hidden(*idx))
+            .map(|(_, tile)| tile)
+            .partition::<Vec<_>, _>(|tile| tile.floating);
+        floating
+            .into_iter()
+            .chain(tiled)
             .filter(|tile| tile.bbox().to_f64().contains(point))
             .find_map(|tile| {
                 let render_location = tile.render_location();
@@ -1213,8 +2504,13 @@ impl<E: WorkspaceElement> Workspace<E> {
             ));
         }
 
-        for (idx, tile) in self.tiles().enumerate() {
-            if idx == self.focused_tile_idx {
+        // Floating tiles should be rendered above tiled ones.
+        let (floating, tiled) = self
+            .tiles()
+            .enumerate()
+            .partition::<Vec<_>, _>(|(_, tile)| tile.floating);
+        for (idx, tile) in floating.into_iter().chain(tiled) {
+            if idx == self.focused_tile_idx || self.is_tile_hidden(idx) {
                 continue;
             }
 
@@ -1222,7 +2518,7 @@ impl<E: WorkspaceElement> Workspace<E> {
                 renderer,
                 &self.output,
                 scale,
-                CONFIG.decoration.normal_window_opacity,
+                CONFIG.decoration.unfocused_window_opacity(),
                 false,
             ));
         }
@@ -1249,6 +2545,8 @@ impl ToString for WorkspaceLayout {
             Self::Tile { .. } => "tile".into(),
             Self::BottomStack { .. } => "bstack".into(),
             Self::CenteredMaster { .. } => "cmaster".into(),
+            Self::Monocle => "monocle".into(),
+            Self::Grid => "grid".into(),
             Self::Floating => "floating".into(),
         }
     }
@@ -1276,6 +2574,10 @@ impl State {
             IpcWorkspaceRequest::ChangeMasterWidthFactor { delta } => {
                 workspace.change_mwfact(delta)
             }
+            IpcWorkspaceRequest::SetLayoutParams { mwfact, nmaster } => {
+                workspace.set_layout_params(mwfact, nmaster)
+            }
+            IpcWorkspaceRequest::Rename { name } => workspace.set_name(name),
             IpcWorkspaceRequest::SelectNextLayout => workspace.select_next_layout(),
             IpcWorkspaceRequest::SelectPreviousLayout => workspace.select_next_layout(),
             IpcWorkspaceRequest::FocusNextWindow => {
@@ -1298,6 +2600,107 @@ impl State {
                     self.set_focus_target(Some(window.into()));
                 }
             }
+            IpcWorkspaceRequest::ArrangeFloating { sender } => {
+                let count = workspace.arrange_floating_tiles();
+                if let Err(err) = sender.send_blocking(count as u32) {
+                    warn!(?err, "Failed to send arranged windows count to IPC!");
+                }
+            }
         }
     }
 }
+
+/// Move the item at `idx` to the master slot, IE the first non-floating slot, putting the previous
+/// master item where it was.
+///
+/// Returns the new index of the item if it moved. Floating items are never promoted.
+fn promote_to_master<T>(
+    items: &mut [T],
+    idx: usize,
+    is_floating: impl Fn(&T) -> bool,
+) -> Option<usize> {
+    if is_floating(items.get(idx)?) {
+        return None;
+    }
+    let master_idx = items.iter().position(|item| !is_floating(item))?;
+    if idx == master_idx {
+        return None;
+    }
+
+    items.swap(idx, master_idx);
+    Some(master_idx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // (name, floating)
+    fn promote(items: &mut [(char, bool)], idx: usize) -> Option<usize> {
+        promote_to_master(items, idx, |(_, floating)| *floating)
+    }
+
+    #[test]
+    fn promote_to_master_swaps_with_master() {
+        let mut items = [('a', false), ('b', false), ('c', false), ('d', false)];
+        assert_eq!(promote(&mut items, 2), Some(0));
+        // The previous master takes the vacated slot, the rest of the stack stays in order.
+        assert_eq!(items.map(|(name, _)| name), ['c', 'b', 'a', 'd']);
+    }
+
+    #[test]
+    fn promote_to_master_skips_floating() {
+        let mut items = [('f', true), ('a', false), ('b', false)];
+        assert_eq!(promote(&mut items, 2), Some(1));
+        assert_eq!(items.map(|(name, _)| name), ['f', 'b', 'a']);
+        // Floating items and the master itself don't move.
+        assert_eq!(promote(&mut items, 0), None);
+        assert_eq!(promote(&mut items, 1), None);
+        assert_eq!(items.map(|(name, _)| name), ['f', 'b', 'a']);
+    }
+
+    fn tile_layout(nmaster: usize) -> WorkspaceLayout {
+        WorkspaceLayout::Tile {
+            nmaster,
+            master_width_factor: 0.5,
+        }
+    }
+
+    fn layout_state(layouts: Vec<WorkspaceLayout>, active_layout_idx: usize) -> LayoutState {
+        LayoutState {
+            layouts,
+            active_layout_idx,
+        }
+    }
+
+    #[test]
+    fn layouts_carry_over_to_empty_workspaces() {
+        // The unplugged output workspace had a tweaked layout, the destination one is empty.
+        let destination = layout_state(vec![tile_layout(1)], 0);
+        let unplugged = layout_state(vec![tile_layout(1), tile_layout(3)], 1);
+        assert_eq!(destination.merge(unplugged.clone(), false), unplugged);
+    }
+
+    #[test]
+    fn layouts_dont_override_workspaces_with_tiles() {
+        let destination = layout_state(vec![tile_layout(2)], 0);
+        let unplugged = layout_state(vec![tile_layout(3)], 0);
+        assert_eq!(destination.clone().merge(unplugged, true), destination);
+    }
+
+    #[test]
+    fn layouts_restore_on_replug() {
+        // Replugging restores the stashed layouts in the new workspaces, clamping the active one.
+        let new_workspace = layout_state(vec![tile_layout(1)], 0);
+        let stashed = layout_state(vec![tile_layout(1), tile_layout(3)], 5);
+        assert_eq!(
+            new_workspace.clone().merge(stashed, false),
+            layout_state(vec![tile_layout(1), tile_layout(3)], 1)
+        );
+        // Nothing stashed keeps the workspace layouts.
+        assert_eq!(
+            new_workspace.clone().merge(layout_state(vec![], 0), false),
+            new_workspace
+        );
+    }
+}
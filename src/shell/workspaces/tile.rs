@@ -13,7 +13,7 @@ use smithay::utils::{IsAlive, Monotonic, Physical, Point, Rectangle, Scale, Size
 use smithay::wayland::compositor::{with_surface_tree_downward, TraversalAction};
 use smithay::wayland::seat::WaylandFocus;
 
-use crate::config::{BorderConfig, ColorConfig, CONFIG};
+use crate::config::{BorderConfig, BorderOverrides, ColorConfig, WindowInputOverrides, CONFIG};
 use crate::renderer::extra_damage::ExtraDamage;
 use crate::renderer::pixel_shader_element::FhtPixelShaderElement;
 use crate::renderer::rounded_element::RoundedCornerElement;
@@ -128,12 +128,65 @@ pub struct WorkspaceTile<E: WorkspaceElement> {
     /// cfacts.
     pub cfact: f32,
 
+    /// Whether this tile is floating.
+    ///
+    /// Floating tiles are not arranged by the workspace layout, and keep whatever geometry they
+    /// were given.
+    pub floating: bool,
+
+    /// The index this tile had inside the workspace before being floated.
+    ///
+    /// This is used to put the tile back where it was when tiling it again.
+    pub last_tiled_index: Option<usize>,
+
+    /// The horizontal location and width this tile had before getting maximized horizontally.
+    ///
+    /// These include the border.
+    pub pre_hmaximize: Option<(i32, i32)>,
+
+    /// The vertical location and height this tile had before getting maximized vertically.
+    ///
+    /// These include the border.
+    pub pre_vmaximize: Option<(i32, i32)>,
+
+    /// Whether this tile is sticky.
+    ///
+    /// Sticky tiles are always floating, and follow the active workspace of their output.
+    pub sticky: bool,
+
+    /// Whether this tile is pinned.
+    ///
+    /// Pinned tiles are left alone by operations acting on every tile of a workspace at once,
+    /// like floating all tiles or arranging floating tiles. Unlike sticky tiles, they stay on
+    /// their workspace.
+    pub pinned: bool,
+
+    /// Whether this tile requested attention while not being focused.
+    ///
+    /// This gets cleared when the tile gets focused.
+    pub urgent: bool,
+
     /// The border configuration for this tile.
     ///
     /// This can be user specified using window rules, falling back to the global configuration if
     /// not set.
     pub border_config: Option<BorderConfig>,
 
+    /// Runtime overrides of the border configuration, set through the IPC.
+    ///
+    /// These are applied on top of `border_config`, and cleared on configuration reload.
+    pub border_overrides: BorderOverrides,
+
+    /// Input configuration overrides applied while this tile is focused.
+    ///
+    /// These are set from the window rules when the tile gets mapped.
+    pub input_overrides: WindowInputOverrides,
+
+    /// Whether the border of this tile is hidden, when it's the only tiled one in its workspace.
+    ///
+    /// See `general.smart_borders`, this is updated when arranging the workspace.
+    pub border_hidden: bool,
+
     /// Since we clip our tile damage for rounded corners, we still have to damage these regions.
     /// This is achieved using this.
     pub rounded_corner_damage: ExtraDamage,
@@ -184,7 +237,17 @@ impl<E: WorkspaceElement> WorkspaceTile<E> {
             element,
             location: Point::default(),
             cfact: 1.0,
+            floating: false,
+            last_tiled_index: None,
+            pre_hmaximize: None,
+            pre_vmaximize: None,
+            sticky: false,
+            pinned: false,
+            urgent: false,
             border_config: None,
+            border_overrides: BorderOverrides::default(),
+            input_overrides: WindowInputOverrides::default(),
+            border_hidden: false,
             rounded_corner_damage: ExtraDamage::default(),
             background_buffer,
             background_buffer_color: buffer_color,
@@ -228,6 +291,28 @@ impl<E: WorkspaceElement> WorkspaceTile<E> {
         );
     }
 
+    /// Set this tile's geometry, from a geometry that doesn't include the border.
+    ///
+    /// See [`WorkspaceTile::geometry_with_border`]
+    pub fn set_geometry_excluding_border(&mut self, new_geo: Rectangle<i32, Local>) {
+        self.set_geometry(self.inflate_by_border(new_geo));
+    }
+
+    /// Get this tile's geometry with the border included, as [`WorkspaceTile::set_geometry`]
+    /// expects it.
+    pub fn geometry_with_border(&self) -> Rectangle<i32, Local> {
+        self.inflate_by_border(self.geometry())
+    }
+
+    fn inflate_by_border(&self, mut geo: Rectangle<i32, Local>) -> Rectangle<i32, Local> {
+        if self.need_border() {
+            let thickness = self.border_config().thickness as i32;
+            geo.loc -= (thickness, thickness).into();
+            geo.size += (2 * thickness, 2 * thickness).into();
+        }
+        geo
+    }
+
     /// Send a pending configure message to the window
     pub fn send_pending_configure(&mut self) {
         self.element.send_pending_configure();
@@ -266,7 +351,7 @@ impl<E: WorkspaceElement> WorkspaceTile<E> {
 
     /// Return whether we need to draw a border for this tile.
     pub fn need_border(&self) -> bool {
-        !self.element.fullscreen()
+        !self.element.fullscreen() && !self.border_hidden
     }
 
     /// Return whether we need to round this tile.
@@ -276,7 +361,8 @@ impl<E: WorkspaceElement> WorkspaceTile<E> {
 
     /// Return the border settings to use when rendering this tile.
     pub fn border_config(&self) -> BorderConfig {
-        self.border_config.unwrap_or(CONFIG.decoration.border)
+        self.border_overrides
+            .merge_with(self.border_config.unwrap_or(CONFIG.decoration.border))
     }
 
     /// Advance this tile's animations.
@@ -369,7 +455,7 @@ impl<E: WorkspaceElement> WorkspaceTile<E> {
                 // parts of their interface (for example OBs does this with the preview window)
                 //
                 // To counter this, we check here if the surface is going to clip.
-                if RoundedCornerElement::will_clip(&e, scale, tile_geo, border_config.radius) {
+                if RoundedCornerElement::will_clip(&e, scale, tile_geo, border_config.radius.0) {
                     let rounded =
                         RoundedCornerElement::new(e, border_config.radius(), tile_geo, scale);
                     need_extra_damage = true;
@@ -409,10 +495,10 @@ impl<E: WorkspaceElement> WorkspaceTile<E> {
                     RoundedOutlineSettings {
                         half_thickness: border_config.half_thickness(),
                         radius: border_config.radius(),
-                        color: if focused {
-                            border_config.focused_color
-                        } else {
-                            border_config.normal_color
+                        color: match border_config.urgent_color {
+                            Some(urgent_color) if self.urgent => urgent_color,
+                            _ if focused => border_config.focused_color,
+                            _ => border_config.normal_color,
                         },
                     },
                 );
@@ -451,7 +537,7 @@ impl<E: WorkspaceElement> WorkspaceTile<E> {
                     border_geo,
                     RoundedOutlineSettings {
                         half_thickness: border_config.half_thickness(),
-                        radius: 0.0, // TODO: Round off solid color element too.
+                        radius: [0.0; 4], // TODO: Round off solid color element too.
                         color: ColorConfig::Solid([
                             self.background_buffer_color[0] * 1.5,
                             self.background_buffer_color[1] * 1.5,
@@ -1,11 +1,12 @@
 use std::cell::{RefCell, RefMut};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Context;
 use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 use smithay::backend::renderer::damage::OutputDamageTracker;
 use smithay::backend::renderer::element::utils::select_dmabuf_feedback;
 use smithay::backend::renderer::element::{
@@ -20,15 +21,16 @@ use smithay::desktop::utils::{
 use smithay::desktop::{layer_map_for_output, PopupManager, Window};
 use smithay::input::keyboard::{KeyboardHandle, Keysym, XkbConfig};
 use smithay::input::pointer::{CursorImageStatus, PointerHandle};
+use smithay::input::touch::TouchHandle;
 use smithay::input::{Seat, SeatState};
-use smithay::output::Output;
+use smithay::output::{Mode, Output, PhysicalProperties, Subpixel};
 use smithay::reexports::calloop::{self, LoopHandle, LoopSignal, RegistrationToken};
 use smithay::reexports::input;
-use smithay::reexports::wayland_server::backend::ClientData;
+use smithay::reexports::wayland_server::backend::{ClientData, GlobalId};
 use smithay::reexports::wayland_server::protocol::wl_shm;
 use smithay::reexports::wayland_server::protocol::wl_surface::WlSurface;
 use smithay::reexports::wayland_server::DisplayHandle;
-use smithay::utils::{Clock, IsAlive, Monotonic, SERIAL_COUNTER};
+use smithay::utils::{Clock, IsAlive, Monotonic, Rectangle, SERIAL_COUNTER};
 use smithay::wayland::compositor::{
     with_surface_tree_downward, CompositorClientState, CompositorState, SurfaceData,
     TraversalAction,
@@ -55,16 +57,18 @@ use smithay::wayland::virtual_keyboard::VirtualKeyboardManagerState;
 use smithay::wayland::xdg_activation::XdgActivationState;
 
 use crate::backend::Backend;
-use crate::config::CONFIG;
+use crate::config::{WindowInputOverrides, WindowMapSettings, CONFIG};
 use crate::egui::Egui;
-use crate::ipc::{IpcOutput, IpcOutputRequest};
+use crate::input::ScreenEdge;
+use crate::ipc::{IpcOutput, IpcOutputRequest, PendingThumbnail, PendingWindowPick};
 use crate::protocols::screencopy::{Screencopy, ScreencopyManagerState};
 use crate::shell::cursor::CursorThemeManager;
+use crate::shell::session::PendingSessionRestore;
 use crate::shell::workspaces::tile::WorkspaceTile;
-use crate::shell::workspaces::WorkspaceSet;
+use crate::shell::workspaces::{LayoutState, Workspace, WorkspaceSet};
 use crate::shell::KeyboardFocusTarget;
 use crate::utils::dbus::DBUS_CONNECTION;
-use crate::utils::geometry::RectCenterExt;
+use crate::utils::geometry::{Local, RectCenterExt};
 use crate::utils::output::OutputExt;
 #[cfg(feature = "xdg-screencast-portal")]
 use crate::utils::pipewire::PipeWire;
@@ -124,9 +128,20 @@ impl State {
     /// otherwise the events won't reach their target clients.
     #[profiling::function]
     pub fn dispatch(&mut self) -> anyhow::Result<()> {
-        self.fht
-            .workspaces_mut()
-            .for_each(|(_, wset)| wset.refresh());
+        let active_output = self.fht.focus_state.output.clone();
+        let mut new_focus = None;
+        for (output, wset) in self.fht.workspaces_mut() {
+            let focus_candidate = wset.refresh();
+            if active_output.as_ref() == Some(output) {
+                new_focus = focus_candidate;
+            }
+        }
+        if let Some(window) = new_focus {
+            self.set_focus_target(Some(window.into()));
+        }
+        self.fht.scratchpads.retain(|_, tile| tile.element.alive());
+        self.fht.stash.retain(|tile| tile.element.alive());
+        self.advertise_window_geometry_changes();
         self.fht.popups.cleanup();
         // Redraw queued outputs.
         {
@@ -251,6 +266,8 @@ pub struct Fht {
     pub keyboard: KeyboardHandle<State>,
     /// The exposed seat pointer.
     pub pointer: PointerHandle<State>,
+    /// The exposed seat touch.
+    pub touch: TouchHandle<State>,
     /// A monotonic clock to tie frame events and input events.
     pub clock: Clock<Monotonic>,
     /// A list of suppressed keys to not pass to the focused client.
@@ -266,6 +283,54 @@ pub struct Fht {
     ///
     /// This handles the cursor theme with its bitmaps and icons (based on the Xcursor standard)
     pub cursor_theme_manager: CursorThemeManager,
+    /// Whether the cursor is hidden since the user is typing.
+    ///
+    /// See `general.cursor.hide_while_typing`
+    pub cursor_hidden_while_typing: bool,
+    /// See `general.cursor.hide_on_touch`
+    pub cursor_hidden_by_touch: bool,
+    /// Whether input is frozen, see [`crate::input::KeyAction::SetInputFrozen`]
+    pub input_frozen: bool,
+    /// The index of the active keyboard layout, see [`State::update_keyboard_layout`]
+    pub keyboard_layout_idx: u32,
+    /// The screen edge the pointer is in, with the pending timer to run its action, if any.
+    pub screen_edge: Option<(ScreenEdge, Option<RegistrationToken>)>,
+    /// The active window pick requested through IPC, if any.
+    pub pending_window_pick: Option<PendingWindowPick>,
+    /// Window thumbnails requested through IPC, rendered with the output holding the window.
+    pub pending_thumbnails: Vec<PendingThumbnail>,
+    /// Output aliases set through IPC, by output name, overriding the configuration.
+    pub output_aliases: HashMap<String, String>,
+    /// The name of the primary output set through IPC, overriding the configuration.
+    ///
+    /// This gets cleared when reloading the configuration.
+    pub primary_output_override: Option<String>,
+    /// Workspace layouts of removed outputs, by output name, restored when they come back.
+    pub stashed_layouts: HashMap<String, Vec<LayoutState>>,
+    /// The last known geometry of each window, by protocol ID, as `(x, y, width, height)`.
+    ///
+    /// This is used to advertise geometry changes to the IPC.
+    pub window_geometries: HashMap<u64, (i32, i32, i32, i32)>,
+    /// Windows hidden in scratchpads, by scratchpad name.
+    ///
+    /// See [`crate::input::KeyAction::ToggleScratchpad`]
+    pub scratchpads: HashMap<String, WorkspaceTile<Window>>,
+    /// Stashed windows, the last one being the most recently stashed.
+    ///
+    /// See [`crate::input::KeyAction::StashAndFocusNext`]
+    pub stash: Vec<WorkspaceTile<Window>>,
+    /// Saved windows waiting to be restored when they get mapped.
+    ///
+    /// See [`crate::input::KeyAction::RestoreSession`]
+    pub session_restore: Option<PendingSessionRestore>,
+    /// The input overrides currently applied to the devices, from the focused window rules.
+    ///
+    /// See [`State::update_input_overrides`]
+    pub input_overrides: WindowInputOverrides,
+    /// The virtual output created when the last real output got removed, if any.
+    ///
+    /// See `general.headless_fallback`
+    pub headless_output: Option<(Output, GlobalId)>,
     /// The list of registered outputs, and their associated [`WorkspaceSet`]s
     pub workspaces: IndexMap<Output, WorkspaceSet<Window>>,
     /// Windows that did not receive an initial configure message.
@@ -285,6 +350,12 @@ pub struct Fht {
     /// Egui debug overlay state.
     pub egui: Egui,
 
+    /// Runtime overrides of the renderer debug configuration.
+    pub debug_overrides: DebugOverrides,
+
+    /// Runtime override of `general.focus_follows_mouse`, until the configuration gets reloaded.
+    pub focus_follows_mouse_override: Option<bool>,
+
     /// PipeWire initialization.
     ///
     /// We can't start PipeWire immediatly since pipewire may not be running yet, but when the
@@ -378,6 +449,7 @@ impl Fht {
             }
         };
         let pointer = seat.add_pointer();
+        let touch = seat.add_touch();
         info!("Initialized wl_seat.");
 
         let cursor_theme_manager = CursorThemeManager::new();
@@ -397,10 +469,27 @@ impl Fht {
             seat_state,
             keyboard,
             pointer,
+            touch,
             focus_state: FocusState::default(),
 
             dnd_icon: None,
             cursor_theme_manager,
+            cursor_hidden_while_typing: false,
+            cursor_hidden_by_touch: false,
+            input_frozen: false,
+            keyboard_layout_idx: 0,
+            screen_edge: None,
+            pending_window_pick: None,
+            pending_thumbnails: vec![],
+            output_aliases: HashMap::new(),
+            primary_output_override: None,
+            stashed_layouts: HashMap::new(),
+            window_geometries: HashMap::new(),
+            scratchpads: HashMap::new(),
+            stash: vec![],
+            session_restore: None,
+            input_overrides: WindowInputOverrides::default(),
+            headless_output: None,
             workspaces: IndexMap::new(),
             pending_windows: vec![],
             unmapped_tiles: vec![],
@@ -409,6 +498,8 @@ impl Fht {
             last_config_error: None,
 
             egui: Egui::default(),
+            debug_overrides: DebugOverrides::default(),
+            focus_follows_mouse_override: None,
 
             #[cfg(feature = "xdg-screencast-portal")]
             pipewire_initialised: std::sync::Once::new(),
@@ -429,6 +520,69 @@ impl Fht {
     }
 }
 
+/// A debug flag that can be toggled at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DebugFlag {
+    /// Show a debug overlay for each output, see `renderer.debug_overlay`
+    DebugOverlay,
+    /// Draw damaged regions, see `renderer.damage_color`
+    DrawDamage,
+}
+
+impl std::str::FromStr for DebugFlag {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "debug-overlay" => Ok(Self::DebugOverlay),
+            "draw-damage" => Ok(Self::DrawDamage),
+            _ => anyhow::bail!("No such debug flag: {s}"),
+        }
+    }
+}
+
+/// Runtime overrides for the renderer debug configuration.
+///
+/// When a value is set, it takes precedence over the one from the configuration. These get reset
+/// when reloading the configuration.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DebugOverrides {
+    pub debug_overlay: Option<bool>,
+    pub draw_damage: Option<bool>,
+}
+
+impl DebugOverrides {
+    /// The damage color used when enabling damage drawing without one set in the configuration.
+    const DEFAULT_DAMAGE_COLOR: [f32; 4] = [1.0, 0.0, 0.0, 0.3];
+
+    /// Set the override of a given debug flag.
+    pub fn set(&mut self, flag: DebugFlag, enabled: bool) {
+        match flag {
+            DebugFlag::DebugOverlay => self.debug_overlay = Some(enabled),
+            DebugFlag::DrawDamage => self.draw_damage = Some(enabled),
+        }
+    }
+
+    /// Get whether to show the debug overlay.
+    pub fn debug_overlay(&self) -> bool {
+        self.debug_overlay.unwrap_or(CONFIG.renderer.debug_overlay)
+    }
+
+    /// Get the color to draw damaged regions with, if we should draw them.
+    pub fn damage_color(&self) -> Option<[f32; 4]> {
+        match self.draw_damage {
+            None => CONFIG.renderer.damage_color,
+            Some(false) => None,
+            Some(true) => Some(
+                CONFIG
+                    .renderer
+                    .damage_color
+                    .unwrap_or(Self::DEFAULT_DAMAGE_COLOR),
+            ),
+        }
+    }
+}
+
 impl Fht {
     /// List all the registered outputs.
     pub fn outputs(&self) -> impl Iterator<Item = &Output> {
@@ -469,7 +623,12 @@ impl Fht {
         trace!(?x, y = 0, "Using fallback output location.");
         output.change_current_state(None, None, None, Some((x, 0).into()));
 
-        let workspace_set = WorkspaceSet::new(output.clone(), self.loop_handle.clone());
+        let mut workspace_set = WorkspaceSet::new(output.clone(), self.loop_handle.clone());
+        if let Some(layout_states) = self.stashed_layouts.remove(&output.name()) {
+            for (workspace, state) in workspace_set.workspaces_mut().zip(layout_states) {
+                workspace.restore_layout_state(state);
+            }
+        }
         self.workspaces.insert(output.clone(), workspace_set);
 
         let pointer_devices = self
@@ -499,6 +658,8 @@ impl Fht {
                 .at(ipc_path, ipc_output)
                 .unwrap());
         }
+        self.update_ipc_primary_output();
+        self.update_ipc_output_alias(&output);
 
         // Focus output now.
         if CONFIG.general.cursor_warps {
@@ -507,7 +668,47 @@ impl Fht {
                 state.move_pointer(center.to_f64());
             });
         }
-        self.focus_state.output = Some(output);
+        self.focus_state.output = Some(output.clone());
+
+        // A real output came back, migrate everything back from the headless output.
+        if self
+            .headless_output
+            .as_ref()
+            .is_some_and(|(headless, _)| *headless != output)
+        {
+            let (headless, global) = self.headless_output.take().unwrap();
+            info!("Removing headless fallback output.");
+            self.remove_output(&headless);
+            self.display_handle.remove_global::<State>(global);
+        }
+    }
+
+    /// Create a virtual output to hold the workspaces when the last real output gets removed.
+    ///
+    /// Nothing gets rendered to it, but clients and their windows survive until a real output
+    /// gets connected again.
+    fn add_headless_output(&mut self) {
+        let (width, height) = CONFIG.general.headless_fallback_size;
+        let mode = Mode {
+            size: (width, height).into(),
+            refresh: 60_000,
+        };
+        let output = Output::new(
+            "HEADLESS-1".to_string(),
+            PhysicalProperties {
+                size: (0, 0).into(),
+                subpixel: Subpixel::Unknown,
+                make: "fht-compositor".to_string(),
+                model: "Headless".to_string(),
+            },
+        );
+        let global = output.create_global::<State>(&self.display_handle);
+        output.set_preferred(mode);
+        output.change_current_state(Some(mode), None, None, None);
+
+        info!("Adding headless fallback output.");
+        self.headless_output = Some((output.clone(), global));
+        self.add_output(output);
     }
 
     /// Unregister an output from the wayland state.
@@ -523,29 +724,42 @@ impl Fht {
             .expect("Tried to remove a non-existing output!");
 
         if self.workspaces.is_empty() {
-            // There's nothing more todo, just adandon everything.
-            self.stop.store(true, std::sync::atomic::Ordering::SeqCst);
-            return;
+            if !CONFIG.general.headless_fallback {
+                // There's nothing more todo, just adandon everything.
+                self.stop.store(true, std::sync::atomic::Ordering::SeqCst);
+                return;
+            }
+
+            // Keep the windows around until a real output comes back.
+            self.add_headless_output();
         }
 
         // Current behaviour:
         //
         // Move each window from each workspace in this removed output wset and bind it to the
-        // first output available, very simple.
+        // primary output, very simple.
         //
         // In other words, if you had a window on ws1, 4, and 8 on this output, they would get
-        // moved to their respective workspace on the first available wset.
-        let wset = self.workspaces.first_mut().unwrap().1;
+        // moved to their respective workspace on the primary output wset.
+        //
+        // The layouts of the workspaces are kept around for when the output comes back, and
+        // carried over to the destination workspace if it had nothing to lose.
+        let layout_states = removed_wset
+            .workspaces()
+            .map(Workspace::layout_state)
+            .collect();
+        self.stashed_layouts.insert(output.name(), layout_states);
+
+        let primary_output = self.primary_output().unwrap().clone();
+        self.update_ipc_primary_output();
+        let wset = self.workspaces.get_mut(&primary_output).unwrap();
 
         for (mut old_workspace, new_workspace) in
             std::iter::zip(removed_wset.workspaces, wset.workspaces_mut())
         {
-            // Little optimizaztion, to avoid recalculating window geometries each time
-            //
-            // Due to how we manage windows, a window can't be in two workspaces at a time, let
-            // alone from different outputs
-            new_workspace.tiles.extend(old_workspace.tiles.drain(..));
-            new_workspace.arrange_tiles();
+            // Don't use take_tiles, the old workspace is getting dropped along with its IPC object.
+            let layout_state = old_workspace.layout_state();
+            new_workspace.merge_tiles(layout_state, old_workspace.tiles.drain(..).collect());
         }
 
         // Cleanly close [`LayerSurface`] instead of letting them know their demise after noticing
@@ -573,6 +787,31 @@ impl Fht {
         wset.arrange();
     }
 
+    /// Move every window of the `from` output to the workspace with the same index on the `to`
+    /// output, like when `from` gets removed.
+    ///
+    /// NOTE: `from` stays enabled, since outputs can't be turned off at runtime.
+    pub fn evacuate_output(&mut self, from: &Output, to: &Output) {
+        info!(from = from.name(), to = to.name(), "Evacuating output.");
+        let workspaces = self
+            .wset_mut_for(from)
+            .workspaces_mut()
+            .map(|workspace| (workspace.layout_state(), workspace.take_tiles()))
+            .collect::<Vec<_>>();
+
+        let wset = self.wset_mut_for(to);
+        for ((layout_state, tiles), workspace) in std::iter::zip(workspaces, wset.workspaces_mut())
+        {
+            workspace.merge_tiles(layout_state, tiles);
+        }
+        wset.refresh();
+        wset.arrange();
+
+        if self.focus_state.output.as_ref() == Some(from) {
+            self.focus_state.output = Some(to.clone());
+        }
+    }
+
     /// Arrange the output workspaces, layer shells, and inform IPC about changes.
     ///
     /// You are expected to call this after you applied your changes to the output, like changing
@@ -582,7 +821,9 @@ impl Fht {
         layer_map_for_output(output).arrange();
 
         let geometry = output.geometry();
-        let refresh_rate = output.current_mode().unwrap().refresh as f32 / 1_000.0;
+        let mode = output.current_mode().unwrap();
+        let refresh_rate = mode.refresh as f32 / 1_000.0;
+        let current_mode = IpcOutput::mode_tuple(mode);
         let scale = output.current_scale();
         let (int_scale, frac_scale) = (scale.integer_scale(), scale.fractional_scale());
         {
@@ -621,6 +862,14 @@ impl Fht {
                         .unwrap();
                 }
 
+                if iface.current_mode != current_mode {
+                    iface.current_mode = current_mode;
+                    iface
+                        .current_mode_changed(iface_ref.signal_context())
+                        .await
+                        .unwrap();
+                }
+
                 if iface.integer_scale != int_scale {
                     iface.integer_scale = int_scale;
                     iface
@@ -640,24 +889,173 @@ impl Fht {
         }
     }
 
-    /// Get the active output, generally the one with the cursor on it, fallbacking to the first
-    /// available output.
+    /// Get the active output, generally the one with the cursor on it, fallbacking to the primary
+    /// output.
     pub fn active_output(&self) -> Output {
         self.focus_state
             .output
             .clone()
-            .unwrap_or_else(|| self.outputs().next().unwrap().clone())
+            .unwrap_or_else(|| self.primary_output().unwrap().clone())
+    }
+
+    /// Get the primary output.
+    ///
+    /// This is the output set using the `SetPrimaryOutput` IPC method, or the first output marked
+    /// with `primary: Some(true)` in the configuration, falling back to the first connected
+    /// output, regardless of hotplug order.
+    pub fn primary_output(&self) -> Option<&Output> {
+        if let Some(name) = self.primary_output_override.as_ref()
+            && let Some(output) = self.outputs().find(|output| output.name() == *name)
+        {
+            return Some(output);
+        }
+
+        self.outputs()
+            .find(|output| {
+                CONFIG
+                    .outputs
+                    .get(&output.name())
+                    .and_then(|config| config.primary)
+                    .unwrap_or(false)
+            })
+            .or_else(|| self.outputs().next())
+    }
+
+    /// Inform IPC about which output is the primary one.
+    pub fn update_ipc_primary_output(&self) {
+        let primary_output = self.primary_output().cloned();
+        for output in self.outputs() {
+            let is_primary = primary_output.as_ref() == Some(output);
+            let path = format!(
+                "/fht/desktop/Compositor/Output/{}",
+                output.name().replace("-", "_")
+            );
+            async_std::task::block_on(async {
+                let Ok(iface_ref) = DBUS_CONNECTION
+                    .object_server()
+                    .interface::<_, IpcOutput>(path.as_str())
+                else {
+                    return;
+                };
+                let mut iface = iface_ref.get_mut();
+
+                if iface.primary != is_primary {
+                    iface.primary = is_primary;
+                    iface
+                        .primary_changed(iface_ref.signal_context())
+                        .await
+                        .unwrap();
+                }
+            });
+        }
     }
 
-    /// Get the output with this name, if any.
+    /// Inform IPC about the color bit depth negotiated for this output.
+    pub fn set_ipc_output_bit_depth(&self, output: &Output, bit_depth: u8) {
+        let path = format!(
+            "/fht/desktop/Compositor/Output/{}",
+            output.name().replace("-", "_")
+        );
+        async_std::task::block_on(async {
+            let Ok(iface_ref) = DBUS_CONNECTION
+                .object_server()
+                .interface::<_, IpcOutput>(path.as_str())
+            else {
+                return;
+            };
+            let mut iface = iface_ref.get_mut();
+
+            if iface.bit_depth != bit_depth {
+                iface.bit_depth = bit_depth;
+                iface
+                    .bit_depth_changed(iface_ref.signal_context())
+                    .await
+                    .unwrap();
+            }
+        });
+    }
+
+    /// Enable or disable this output, informing IPC about it.
+    ///
+    /// See [`OutputState::enabled`]
+    pub fn set_output_enabled(&self, output: &Output, enabled: bool) {
+        OutputState::get(output).enabled = enabled;
+
+        let path = format!(
+            "/fht/desktop/Compositor/Output/{}",
+            output.name().replace("-", "_")
+        );
+        async_std::task::block_on(async {
+            let Ok(iface_ref) = DBUS_CONNECTION
+                .object_server()
+                .interface::<_, IpcOutput>(path.as_str())
+            else {
+                return;
+            };
+            let mut iface = iface_ref.get_mut();
+
+            if iface.enabled != enabled {
+                iface.enabled = enabled;
+                iface
+                    .enabled_changed(iface_ref.signal_context())
+                    .await
+                    .unwrap();
+            }
+        });
+    }
+
+    /// Get the output with this name or alias, if any.
     pub fn output_named(&self, name: &str) -> Option<Output> {
         if name == "active" {
             Some(self.active_output())
         } else {
-            self.outputs().find(|o| &o.name() == name).cloned()
+            self.outputs()
+                .find(|o| &o.name() == name)
+                .or_else(|| {
+                    self.outputs()
+                        .find(|o| self.output_alias(o).is_some_and(|alias| alias == name))
+                })
+                .cloned()
         }
     }
 
+    /// Get the alias of this output, set through IPC or in the configuration.
+    pub fn output_alias(&self, output: &Output) -> Option<String> {
+        let name = output.name();
+        self.output_aliases.get(&name).cloned().or_else(|| {
+            CONFIG
+                .outputs
+                .get(&name)
+                .and_then(|config| config.alias.clone())
+        })
+    }
+
+    /// Inform IPC about the alias of this output.
+    pub fn update_ipc_output_alias(&self, output: &Output) {
+        let alias = self.output_alias(output).unwrap_or_default();
+        let path = format!(
+            "/fht/desktop/Compositor/Output/{}",
+            output.name().replace("-", "_")
+        );
+        async_std::task::block_on(async {
+            let Ok(iface_ref) = DBUS_CONNECTION
+                .object_server()
+                .interface::<_, IpcOutput>(path.as_str())
+            else {
+                return;
+            };
+            let mut iface = iface_ref.get_mut();
+
+            if iface.alias != alias {
+                iface.alias = alias;
+                iface
+                    .alias_changed(iface_ref.signal_context())
+                    .await
+                    .unwrap();
+            }
+        });
+    }
+
     /// List all the outputs and a reference to their associated workspace set.
     pub fn workspaces(&self) -> impl Iterator<Item = (&Output, &WorkspaceSet<Window>)> {
         self.workspaces.iter()
@@ -1025,6 +1423,12 @@ pub struct OutputState {
     /// The custom damage tracker for this output.
     /// This is for screencast.
     pub damage_tracker: OutputDamageTracker,
+
+    /// Whether this output is enabled.
+    ///
+    /// Disconnected outputs are kept around for a moment before getting removed, they are
+    /// disabled in the meantime.
+    pub enabled: bool,
 }
 
 impl OutputState {
@@ -1045,6 +1449,7 @@ impl OutputState {
                 current_frame_sequence: 0,
                 pending_screencopy: None,
                 damage_tracker: OutputDamageTracker::from_output(output),
+                enabled: true,
             })
         });
     }
@@ -1127,4 +1532,10 @@ pub struct UnmappedTile {
     pub inner: WorkspaceTile<Window>,
     pub last_output: Option<Output>,
     pub last_workspace_idx: Option<usize>,
+    /// The map settings from the window rules matching this tile's window.
+    pub map_settings: WindowMapSettings,
+    /// The geometry to give this tile when mapping it, from a restored session.
+    ///
+    /// This is only set for floating tiles.
+    pub session_geometry: Option<Rectangle<i32, Local>>,
 }
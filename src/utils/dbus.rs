@@ -1,7 +1,11 @@
 use std::sync::LazyLock;
+use std::time::Duration;
 
 use zbus::blocking;
 
+/// The service name of the compositor on the session bus.
+pub const DBUS_NAME: &str = "fht.desktop.Compositor";
+
 /// The session connection of the compositor to ensure the `fht.desktop.Compositor` service name on
 /// the session connection.
 ///
@@ -10,7 +14,40 @@ use zbus::blocking;
 pub static DBUS_CONNECTION: LazyLock<blocking::Connection> = LazyLock::new(|| {
     let session = blocking::ConnectionBuilder::session().expect("Failed to open session bus!");
     let connection = session
-        .name("fht.desktop.Compositor")
+        .name(DBUS_NAME)
         .expect("Failed to reserve service name!");
     connection.build().unwrap()
 });
+
+/// Check whether another compositor instance owns the service name on the session bus.
+///
+/// The bus releases the name when its owner dies, so a crashed instance never leaves it behind.
+pub fn name_has_owner() -> zbus::Result<bool> {
+    let connection = blocking::Connection::session()?;
+    let proxy = blocking::fdo::DBusProxy::new(&connection)?;
+    Ok(proxy.name_has_owner(DBUS_NAME.try_into()?)?)
+}
+
+/// Ask the compositor instance owning the service name to quit, and wait for it to release it.
+pub fn replace_running_instance() -> zbus::Result<()> {
+    let connection = blocking::Connection::session()?;
+    connection.call_method(
+        Some(DBUS_NAME),
+        "/fht/desktop/Compositor",
+        Some("fht.desktop.Compositor.Ipc"),
+        "Quit",
+        &(),
+    )?;
+
+    // The other instance has to go through its event loop to stop, give it some time.
+    for _ in 0..50 {
+        if !name_has_owner()? {
+            return Ok(());
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
+    Err(zbus::Error::Failure(
+        "The running instance did not quit in time!".to_string(),
+    ))
+}
@@ -24,6 +24,18 @@ pub struct Frame {
     pub duration_elements: Duration,
     pub duration_render: Duration,
     pub duration_screencopy: Option<Duration>,
+    pub presentation_latency: Duration,
+}
+
+/// Frame timing statistics of a surface, computed from its most recent frames.
+#[derive(Debug, Clone)]
+pub struct RenderStats {
+    /// The frame times of the most recent frames, oldest first.
+    pub frame_times: Vec<Duration>,
+    /// The average time between the start of a frame and its presentation.
+    pub avg_presentation_latency: Duration,
+    /// How many of the tracked frames took longer than a refresh cycle to get presented.
+    pub missed_frames: usize,
 }
 
 impl Frame {
@@ -45,12 +57,14 @@ impl From<PendingFrame> for Frame {
             duration_elements: pending.duration_elements.unwrap_or(Duration::ZERO),
             duration_render: pending.duration_render.unwrap_or(Duration::ZERO),
             duration_screencopy: pending.duration_screencast,
+            presentation_latency: Duration::ZERO,
         }
     }
 }
 
 impl Fps {
     const WINDOW_SIZE: usize = 360;
+    const RECENT_FRAMES: usize = 60;
 
     pub fn start(&mut self) {
         self.pending_frame = Some(PendingFrame {
@@ -89,7 +103,9 @@ impl Fps {
 
     pub fn displayed(&mut self) {
         if let Some(frame) = self.pending_frame.take() {
-            self.frames.push_back(frame.into());
+            let mut frame = Frame::from(frame);
+            frame.presentation_latency = Instant::now().duration_since(frame.start);
+            self.frames.push_back(frame);
             while self.frames.len() > Fps::WINDOW_SIZE {
                 self.frames.pop_front();
             }
@@ -128,6 +144,38 @@ impl Fps {
             / window as u32
     }
 
+    /// Compute the [`RenderStats`] of the tracked frames.
+    ///
+    /// Frames that took longer than `refresh_interval` to get presented are counted as missed.
+    pub fn stats(&self, refresh_interval: Duration) -> RenderStats {
+        let frame_times = self
+            .frames
+            .iter()
+            .skip(self.frames.len().saturating_sub(Fps::RECENT_FRAMES))
+            .map(Frame::frame_time)
+            .collect();
+        let avg_presentation_latency = if self.frames.is_empty() {
+            Duration::ZERO
+        } else {
+            self.frames
+                .iter()
+                .map(|f| f.presentation_latency)
+                .sum::<Duration>()
+                / (self.frames.len() as u32)
+        };
+        let missed_frames = self
+            .frames
+            .iter()
+            .filter(|f| f.presentation_latency > refresh_interval)
+            .count();
+
+        RenderStats {
+            frame_times,
+            avg_presentation_latency,
+            missed_frames,
+        }
+    }
+
     pub fn avg_fps(&self) -> f64 {
         if self.frames.is_empty() {
             return 0.0;